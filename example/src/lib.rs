@@ -1,12 +1,45 @@
 use rustsynth::{
     core::CoreRef,
     filter::{Filter, FilterDependency, FilterMode, RequestPattern},
+    format::{ColorFamily, ComponentVisitor, Sample, SampleType, VideoFormat},
     frame::{Frame, FrameContext},
     map::MapRef,
     node::Node,
     vapoursynth_plugin,
 };
 
+/// Inverts every plane of `src` into `dst`, for any sample type.
+struct InvertPlanes<'a, 'core> {
+    src: &'a Frame<'core>,
+    dst: &'a mut Frame<'core>,
+    format: VideoFormat,
+}
+
+impl ComponentVisitor for InvertPlanes<'_, '_> {
+    type Output = ();
+
+    fn visit<T: Sample>(self, bits_per_sample: i32) {
+        for plane in 0..self.format.num_planes {
+            // Float chroma is centered on zero, everything else goes from zero to the peak
+            let peak = if plane > 0
+                && self.format.color_family == ColorFamily::YUV
+                && self.format.sample_type == SampleType::Float
+            {
+                T::ZERO
+            } else {
+                T::peak(bits_per_sample)
+            };
+            let src_rows = self.src.rows::<T>(plane).unwrap();
+            let dst_rows = self.dst.rows_mut::<T>(plane).unwrap();
+            for (src_row, dst_row) in src_rows.zip(dst_rows) {
+                for (src, dst) in src_row.iter().zip(dst_row) {
+                    *dst = peak - *src;
+                }
+            }
+        }
+    }
+}
+
 #[vapoursynth_plugin]
 mod plugin {
     use rustsynth::{ffi, plugin::PluginConfigFlags, vapoursynth_filter, MakeVersion};
@@ -61,24 +94,13 @@ mod plugin {
             let width = src.get_width(0);
             let mut dst = Frame::new_video_frame(&core, width, height, &vf, Some(&src));
 
-            // Actually do the invert operation
-            for plane in 0..vf.num_planes {
-                let mut srcp = src.get_read_ptr(plane);
-                let mut dstp = dst.get_write_ptr(plane);
-                let stride = src.get_stride(plane) as usize;
-                let h = src.get_height(plane) as usize;
-                let w = src.get_width(plane) as usize;
-
-                unsafe {
-                    for _y in 0..h {
-                        for x in 0..w {
-                            *dstp.add(x) = !(*srcp.add(x));
-                        }
-                        srcp = srcp.add(stride);
-                        dstp = dstp.add(stride);
-                    }
-                }
-            }
+            // Actually do the invert operation, for whichever sample type the clip has
+            vf.dispatch(InvertPlanes {
+                src: &src,
+                dst: &mut dst,
+                format: vf,
+            })
+            .map_err(|e| e.to_string())?;
             Ok(dst)
         }
     }
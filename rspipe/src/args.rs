@@ -0,0 +1,58 @@
+use rustsynth::map::{Map, MapRef, MapResult};
+use rustsynth::vsscript::{Environment, ScriptResult};
+
+/// A `--arg` value, typed when the key has a `:type` suffix.
+pub enum ScriptArg {
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+/// Parses `key=value` or `key:type=value`, where type is `int`, `float` or `str`.
+pub fn parse(arg: &str) -> Result<(String, ScriptArg), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid argument format: {}. Use key=value", arg))?;
+    let Some((key, ty)) = key.split_once(':') else {
+        return Ok((key.to_string(), ScriptArg::Str(value.to_string())));
+    };
+    let value = match ty {
+        "int" => ScriptArg::Int(
+            value
+                .parse()
+                .map_err(|e| format!("Invalid int for {}: {}", key, e))?,
+        ),
+        "float" => ScriptArg::Float(
+            value
+                .parse()
+                .map_err(|e| format!("Invalid float for {}: {}", key, e))?,
+        ),
+        "str" => ScriptArg::Str(value.to_string()),
+        _ => {
+            return Err(format!(
+                "Unknown argument type {} for {}. Use int, float or str",
+                ty, key
+            ));
+        }
+    };
+    Ok((key.to_string(), value))
+}
+
+impl ScriptArg {
+    pub fn set_in(&self, map: &mut MapRef<'_>, key: &str) -> MapResult<()> {
+        match self {
+            Self::Int(value) => map.set_int(key, *value),
+            Self::Float(value) => map.set_float(key, *value),
+            Self::Str(value) => map.set(key, value),
+        }
+    }
+}
+
+/// Sets the parsed arguments as variables in the script environment.
+pub fn set_all(environment: &Environment, args: &[(String, ScriptArg)]) -> ScriptResult<()> {
+    let mut vars_map = Map::new()?;
+    for (key, value) in args {
+        value.set_in(&mut vars_map, key)?;
+    }
+    environment.set_variables(&vars_map)
+}
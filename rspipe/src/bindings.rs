@@ -0,0 +1,51 @@
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use rustsynth::codegen::BindingGenerator;
+use rustsynth::core::{CoreCreationFlags, CoreRef};
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+
+pub fn command() -> Command {
+    Command::new("bindings")
+        .about("Generate typed Rust bindings for the installed plugins")
+        .arg(
+            Arg::new("namespace")
+                .short('n')
+                .long("namespace")
+                .help("Only generate bindings for this namespace (repeatable)")
+                .value_name("NAMESPACE")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("Write the bindings to a file instead of stdout")
+                .value_name("FILE"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) {
+    let core = CoreRef::new(CoreCreationFlags::NONE);
+
+    let mut generator = BindingGenerator::new();
+    if let Some(namespaces) = matches.get_many::<String>("namespace") {
+        for namespace in namespaces {
+            if core.plugin_by_namespace(namespace).is_none() {
+                eprintln!("No plugin found with namespace {}", namespace);
+                process::exit(1);
+            }
+            generator = generator.namespace(namespace);
+        }
+    }
+    let source = generator.generate(&core);
+
+    let result = match matches.get_one::<String>("output") {
+        Some(path) => fs::write(path, source),
+        None => io::stdout().write_all(source.as_bytes()),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to write bindings: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,181 @@
+/// One comma separated item of a `--frames` selection.
+enum Item {
+    /// A single frame.
+    Single(i64),
+    /// An inclusive range, open ends default to the first and last frame.
+    Range(Option<i64>, Option<i64>),
+    /// A Python slice `start:stop:step`, `stop` being exclusive.
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+}
+
+/// A frame selection such as `0-100,500,-10-` or `::2`. Negative numbers count from the end,
+/// `-1` being the last frame.
+pub struct Selection {
+    items: Vec<Item>,
+}
+
+fn number(s: &str) -> Result<Option<i64>, String> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse()
+        .map(Some)
+        .map_err(|_| format!("Invalid frame number '{}'", s))
+}
+
+impl std::str::FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let items = s
+            .split(',')
+            .map(|item| {
+                let item = item.trim();
+                if item.contains(':') {
+                    let parts: Vec<_> = item.split(':').collect();
+                    if parts.len() > 3 {
+                        return Err(format!("Invalid frame slice '{}'", item));
+                    }
+                    let part = |i: usize| parts.get(i).map_or(Ok(None), |part| number(part));
+                    return Ok(Item::Slice(part(0)?, part(1)?, part(2)?));
+                }
+                // A leading '-' belongs to the first number, the next one separates the range
+                let sign = usize::from(item.starts_with('-'));
+                match item[sign..].find('-') {
+                    Some(i) => Ok(Item::Range(
+                        number(&item[..sign + i])?,
+                        number(&item[sign + i + 1..])?,
+                    )),
+                    None => number(item)?
+                        .map(Item::Single)
+                        .ok_or_else(|| "Empty frame selection item".to_string()),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { items })
+    }
+}
+
+/// Resolves a possibly negative frame number against `count` frames.
+pub fn resolve_index(n: i64, count: i64) -> Result<i64, String> {
+    let resolved = if n < 0 { count + n } else { n };
+    if (0..count).contains(&resolved) {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "Frame {} is out of range, the clip has {} frames",
+            n, count
+        ))
+    }
+}
+
+impl Selection {
+    /// Lists the selected frames of a clip with `count` frames, in the order they were given.
+    pub fn resolve(&self, count: i64) -> Result<Vec<i32>, String> {
+        let mut frames = Vec::new();
+        for item in &self.items {
+            match *item {
+                Item::Single(n) => frames.push(resolve_index(n, count)?),
+                Item::Range(start, end) => {
+                    let start = start.map_or(Ok(0), |n| resolve_index(n, count))?;
+                    let end = end.map_or(Ok(count - 1), |n| resolve_index(n, count))?;
+                    if start > end {
+                        return Err(format!("Frame range {}-{} is empty", start, end));
+                    }
+                    frames.extend(start..=end);
+                }
+                Item::Slice(start, stop, step) => {
+                    let step = step.unwrap_or(1);
+                    if step == 0 {
+                        return Err("Frame slice step can't be zero".to_string());
+                    }
+                    // Python's slice.indices()
+                    let clamp = |n: i64, low: i64, high: i64| {
+                        let n = if n < 0 { n + count } else { n };
+                        n.clamp(low, high)
+                    };
+                    if step > 0 {
+                        let start = start.map_or(0, |n| clamp(n, 0, count));
+                        let stop = stop.map_or(count, |n| clamp(n, 0, count));
+                        frames.extend((start..stop).step_by(step as usize));
+                    } else {
+                        let start = start.map_or(count - 1, |n| clamp(n, -1, count - 1));
+                        let stop = stop.map_or(-1, |n| clamp(n, -1, count - 1));
+                        frames.extend(
+                            (stop + 1..=start)
+                                .rev()
+                                .step_by(step.unsigned_abs() as usize),
+                        );
+                    }
+                }
+            }
+        }
+        Ok(frames.into_iter().map(|n| n as i32).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(selection: &str, count: i64) -> Result<Vec<i32>, String> {
+        selection.parse::<Selection>()?.resolve(count)
+    }
+
+    #[test]
+    fn test_ranges_and_singles() {
+        let mut expected: Vec<i32> = (0..=100).collect();
+        expected.push(500);
+        expected.extend(990..1000);
+        assert_eq!(resolve("0-100,500,-10-", 1000).unwrap(), expected);
+
+        assert_eq!(
+            resolve("-10--5", 20).unwrap(),
+            (10..=15).collect::<Vec<_>>()
+        );
+        assert_eq!(resolve("-3", 20).unwrap(), [17]);
+        assert_eq!(resolve("2-", 5).unwrap(), [2, 3, 4]);
+        // Frames are listed in the order they were given
+        assert_eq!(resolve(" 5, 1-2 ", 10).unwrap(), [5, 1, 2]);
+    }
+
+    #[test]
+    fn test_slices() {
+        assert_eq!(resolve("::2", 5).unwrap(), [0, 2, 4]);
+        assert_eq!(resolve("::-1", 5).unwrap(), [4, 3, 2, 1, 0]);
+        assert_eq!(resolve("1:4", 5).unwrap(), [1, 2, 3]);
+        assert_eq!(resolve("-2:", 5).unwrap(), [3, 4]);
+        assert_eq!(resolve("3:0:-2", 5).unwrap(), [3, 1]);
+        // Like Python, slices are clamped to the clip
+        assert_eq!(resolve("3:100", 5).unwrap(), [3, 4]);
+        assert_eq!(resolve("100:", 5).unwrap(), []);
+    }
+
+    #[test]
+    fn test_invalid_selections() {
+        assert_eq!(
+            resolve("::0", 5).unwrap_err(),
+            "Frame slice step can't be zero"
+        );
+        assert_eq!(
+            resolve("5", 5).unwrap_err(),
+            "Frame 5 is out of range, the clip has 5 frames"
+        );
+        assert_eq!(
+            resolve("-6", 5).unwrap_err(),
+            "Frame -6 is out of range, the clip has 5 frames"
+        );
+        assert!(resolve("0-5", 5).is_err());
+        assert_eq!(resolve("3-1", 5).unwrap_err(), "Frame range 3-1 is empty");
+        assert_eq!(
+            resolve("1,,2", 5).unwrap_err(),
+            "Empty frame selection item"
+        );
+        assert_eq!(resolve("a", 5).unwrap_err(), "Invalid frame number 'a'");
+        assert_eq!(resolve("-", 5).unwrap_err(), "Invalid frame number '-'");
+        assert_eq!(
+            resolve("1:2:3:4", 5).unwrap_err(),
+            "Invalid frame slice '1:2:3:4'"
+        );
+    }
+}
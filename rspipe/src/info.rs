@@ -0,0 +1,153 @@
+use rustsynth::{
+    format::{AudioInfo, MediaType, VideoInfo},
+    frame::Frame,
+    map::{DataType, MapValues},
+    node::Node,
+    vsscript::Environment,
+};
+
+use crate::json;
+
+/// The values of a frame property, always as an array even when there is only one. Data that
+/// isn't text is written as its size, nodes, frames and functions as their description.
+fn property(values: &MapValues<'_, '_>) -> String {
+    let values: Vec<String> = match values {
+        MapValues::Ints(ints) => ints.iter().map(ToString::to_string).collect(),
+        MapValues::Floats(floats) => floats.iter().map(|&f| json::number(f)).collect(),
+        MapValues::Data(data) => data
+            .iter()
+            .map(|(data, hint)| match (hint, std::str::from_utf8(data)) {
+                (DataType::String | DataType::Unknown, Ok(s)) => json::string(s),
+                _ => json::object([("bytes", data.len().to_string())]),
+            })
+            .collect(),
+        MapValues::Nodes(nodes) => nodes
+            .iter()
+            .map(|node| json::string(&MapValues::Nodes(vec![node.clone()]).to_string()))
+            .collect(),
+        MapValues::Frames(frames) => frames
+            .iter()
+            .map(|frame| json::string(&MapValues::Frames(vec![frame.clone()]).to_string()))
+            .collect(),
+        MapValues::Functions(functions) => functions
+            .iter()
+            .map(|function| json::string(&MapValues::Functions(vec![function.clone()]).to_string()))
+            .collect(),
+    };
+    json::array(values)
+}
+
+fn properties(frame: &Frame<'_>) -> String {
+    let properties = frame.properties();
+    json::object(
+        properties
+            .iter()
+            .map(|(key, values)| (key, property(&values)))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn video_fields(node: &Node<'_>, video_info: &VideoInfo) -> Vec<(&'static str, String)> {
+    let format = &video_info.format;
+    // A variable frame rate has no fps or duration
+    let fps =
+        (video_info.fps_den > 0).then(|| video_info.fps_num as f64 / video_info.fps_den as f64);
+    let first_frame = if video_info.num_frames > 0 {
+        node.get_frame(0).ok()
+    } else {
+        None
+    };
+    vec![
+        ("width", video_info.width.to_string()),
+        ("height", video_info.height.to_string()),
+        (
+            "format",
+            json::optional_string(format.get_name().as_deref()),
+        ),
+        (
+            "color_family",
+            json::string(&format!("{:?}", format.color_family)),
+        ),
+        (
+            "sample_type",
+            json::string(&format!("{:?}", format.sample_type)),
+        ),
+        ("bits_per_sample", format.bits_per_sample.to_string()),
+        ("bytes_per_sample", format.bytes_per_sample.to_string()),
+        ("subsampling_w", format.sub_sampling_w.to_string()),
+        ("subsampling_h", format.sub_sampling_h.to_string()),
+        ("num_planes", format.num_planes.to_string()),
+        ("frames", video_info.num_frames.to_string()),
+        ("fps_num", video_info.fps_num.to_string()),
+        ("fps_den", video_info.fps_den.to_string()),
+        ("fps", fps.map_or_else(|| "null".to_string(), json::number)),
+        (
+            "duration",
+            fps.map_or_else(
+                || "null".to_string(),
+                |fps| json::number(f64::from(video_info.num_frames) / fps),
+            ),
+        ),
+        (
+            "props",
+            first_frame
+                .as_ref()
+                .map_or_else(|| "null".to_string(), properties),
+        ),
+    ]
+}
+
+fn audio_fields(audio_info: &AudioInfo) -> Vec<(&'static str, String)> {
+    let format = &audio_info.format;
+    vec![
+        (
+            "format",
+            json::optional_string(format.get_name().as_deref()),
+        ),
+        (
+            "sample_type",
+            json::string(&format!("{:?}", format.sample_type)),
+        ),
+        ("bits_per_sample", format.bits_per_sample.to_string()),
+        ("bytes_per_sample", format.bytes_per_sample.to_string()),
+        ("channels", format.num_channels.to_string()),
+        (
+            "channel_layout",
+            json::string(&format.channel_layout.to_string()),
+        ),
+        ("sample_rate", audio_info.sample_rate.to_string()),
+        ("samples", audio_info.num_samples.to_string()),
+        ("frames", audio_info.num_frames.to_string()),
+        (
+            "duration",
+            json::number(audio_info.num_samples as f64 / f64::from(audio_info.sample_rate)),
+        ),
+    ]
+}
+
+/// Prints every output of the script as a JSON object to stdout.
+pub fn print_json(environment: &Environment) {
+    let outputs = environment.outputs().into_iter().map(|output| {
+        let mut fields = vec![
+            ("index", output.index.to_string()),
+            ("alt_output", i32::from(output.alt_output_mode).to_string()),
+        ];
+        match output.media_type {
+            MediaType::Video => {
+                fields.push(("type", json::string("video")));
+                fields.push(("alpha", output.alpha.is_some().to_string()));
+                if let Some(video_info) = output.node.video_info() {
+                    fields.extend(video_fields(&output.node, &video_info));
+                }
+            }
+            MediaType::Audio => {
+                fields.push(("type", json::string("audio")));
+                if let Some(audio_info) = output.node.audio_info() {
+                    fields.extend(audio_fields(&audio_info));
+                }
+            }
+        }
+        json::object(fields)
+    });
+    println!("{}", json::object([("outputs", json::array(outputs))]));
+}
@@ -1,28 +1,43 @@
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use rustsynth::{
     core::{CoreCreationFlags, CoreRef},
-    map::Map,
-    vsscript::Environment,
+    format::AudioInfo,
+    vsscript::{Environment, ScriptError, ScriptWatcher},
 };
 use std::collections::HashMap;
 use std::io::{self, BufWriter, Write};
 use std::process;
-use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+mod args;
+mod bindings;
+mod frames;
+mod info;
+mod json;
 mod output;
+mod plugins;
 mod progress;
+mod wave;
+mod y4m;
 
 use output::OutputWriter;
-use progress::ProgressTracker;
+use progress::{ProgressFormat, ProgressTracker};
+
+/// The `__file__` of a script read from stdin.
+const STDIN_SCRIPT_NAME: &str = "<stdin>";
 
 fn main() {
     let matches = Command::new("rspipe")
         .version("0.1.0")
         .disable_version_flag(true)
         .about("VapourSynth script processor using rustsynth")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(bindings::command())
+        .subcommand(plugins::command())
         .arg(
             Arg::new("script")
-                .help("VapourSynth script file (.vpy)")
+                .help("VapourSynth script file (.vpy), or '-' to read it from stdin")
                 .required_unless_present("version")
                 .index(1),
         )
@@ -31,31 +46,43 @@ fn main() {
                 .help("Output file (use '-' for stdout, '--' for no output)")
                 .required_unless_present("version")
                 .required_unless_present("info")
+                .required_unless_present("watch")
                 .index(2),
         )
         .arg(
             Arg::new("arg")
                 .short('a')
                 .long("arg")
-                .help("Argument to pass to the script environment")
-                .value_name("key=value")
+                .help("Argument to pass to the script environment, typed with key:int=, key:float= or key:str=")
+                .value_name("key[:type]=value")
                 .action(ArgAction::Append),
         )
         .arg(
             Arg::new("start")
                 .short('s')
                 .long("start")
-                .help("Set output frame/sample range start")
+                .help("Set output frame/sample range start, negative values count from the end")
                 .value_name("N")
-                .value_parser(clap::value_parser!(usize)),
+                .allow_negative_numbers(true)
+                .value_parser(clap::value_parser!(i64)),
         )
         .arg(
             Arg::new("end")
                 .short('e')
                 .long("end")
-                .help("Set output frame/sample range end (inclusive)")
+                .help("Set output frame/sample range end (inclusive), negative values count from the end")
                 .value_name("N")
-                .value_parser(clap::value_parser!(usize)),
+                .allow_negative_numbers(true)
+                .value_parser(clap::value_parser!(i64)),
+        )
+        .arg(
+            Arg::new("frames")
+                .short('f')
+                .long("frames")
+                .help("Select the output frames with ranges, single frames and slices, such as 0-100,500,-10- or ::2")
+                .value_name("SELECTION")
+                .allow_hyphen_values(true)
+                .conflicts_with_all(["start", "end"]),
         )
         .arg(
             Arg::new("outputindex")
@@ -86,8 +113,20 @@ fn main() {
             Arg::new("progress")
                 .short('p')
                 .long("progress")
-                .help("Print progress to stderr")
-                .action(ArgAction::SetTrue),
+                .help("Print progress to stderr, as text or as one JSON event per line")
+                .value_name("FORMAT")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("text")
+                .value_parser(["text", "json"]),
+        )
+        .arg(
+            Arg::new("progress-fd")
+                .long("progress-fd")
+                .help("Print progress to this file descriptor instead of stderr")
+                .value_name("FD")
+                .requires("progress")
+                .value_parser(clap::value_parser!(i32)),
         )
         .arg(
             Arg::new("info")
@@ -96,6 +135,20 @@ fn main() {
                 .help("Print all set output node info and exit")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the info of every output as JSON to stdout")
+                .requires("info")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .help("Re-evaluate the script whenever it or a local module changes and print the output info")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("version")
                 .short('v')
@@ -111,10 +164,42 @@ fn main() {
         return;
     }
 
+    match matches.subcommand() {
+        Some(("bindings", sub_matches)) => {
+            bindings::run(sub_matches);
+            return;
+        }
+        Some(("plugins", sub_matches)) => {
+            plugins::run(sub_matches);
+            return;
+        }
+        _ => {}
+    }
+
     let script_path = matches.get_one::<String>("script").unwrap();
     let binding = "--".to_string();
     let outfile = matches.get_one::<String>("outfile").unwrap_or(&binding);
 
+    let mut script_args = Vec::new();
+    for arg in matches.get_many::<String>("arg").into_iter().flatten() {
+        match args::parse(arg) {
+            Ok(parsed) => script_args.push(parsed),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+    let output_index = *matches.get_one::<i32>("outputindex").unwrap();
+
+    if matches.get_flag("watch") {
+        if script_path == "-" {
+            eprintln!("Can't watch a script read from stdin");
+            process::exit(1);
+        }
+        watch(script_path, output_index, script_args);
+    }
+
     // Initialize VapourSynth
     let core = CoreRef::new(CoreCreationFlags::NONE);
     let mut environment = match Environment::new(&core) {
@@ -127,37 +212,32 @@ fn main() {
 
     Environment::load_api(core.info().api_version);
     // Set script arguments
-    if let Some(args) = matches.get_many::<String>("arg") {
-        let mut script_args = HashMap::new();
-        for arg in args {
-            if let Some((key, value)) = arg.split_once('=') {
-                script_args.insert(key.to_string(), value.to_string());
-            } else {
-                eprintln!("Invalid argument format: {}. Use key=value", arg);
-                process::exit(1);
-            }
-        }
-
-        let mut vars_map = Map::new().unwrap();
-        for (key, value) in script_args {
-            if let Err(e) = vars_map.set(&key, &value) {
-                eprintln!("Failed to set script variable {}: {}", key, e);
-                process::exit(1);
-            }
-        }
-        if let Err(e) = environment.set_variables(&vars_map) {
-            eprintln!("Failed to set script variables: {}", e);
-            process::exit(1);
-        }
+    if let Err(e) = args::set_all(&environment, &script_args) {
+        eprintln!("Failed to set script variables: {}", e);
+        process::exit(1);
     }
 
     // Evaluate script
-    if let Err(e) = environment.eval_file(script_path) {
-        eprintln!("Script evaluation failed: {}", e);
+    let result = if script_path == "-" {
+        environment.eval_reader(io::stdin().lock(), STDIN_SCRIPT_NAME)
+    } else {
+        environment.eval_file(script_path)
+    };
+    if let Err(e) = result {
+        let name = if script_path == "-" {
+            STDIN_SCRIPT_NAME
+        } else {
+            script_path
+        };
+        report_script_error(name, &e);
         process::exit(1);
     }
 
-    let output_index = *matches.get_one::<i32>("outputindex").unwrap();
+    if matches.get_flag("json") {
+        info::print_json(&environment);
+        return;
+    }
+
     let node = match environment.get_output(output_index) {
         Some(node) => node,
         None => {
@@ -172,27 +252,35 @@ fn main() {
         return;
     }
 
+    let num_requests = *matches
+        .get_one::<usize>("requests")
+        .unwrap_or(&environment.get_core().info().num_threads);
+
+    if let Some(audio_info) = node.audio_info() {
+        write_audio(&matches, &node, &audio_info, outfile, num_requests);
+        return;
+    }
+
     let video_info = match node.video_info() {
         Some(info) => info,
         None => {
-            eprintln!("Node has no video info (audio nodes not yet supported)");
+            eprintln!("Node has neither video nor audio info");
             process::exit(1);
         }
     };
 
-    // Determine frame range
-    let start_frame = matches.get_one::<i32>("start").copied().unwrap_or(0);
-    let end_frame = matches
-        .get_one::<i32>("end")
-        .copied()
-        .unwrap_or(video_info.num_frames - 1);
-
-    if start_frame > end_frame {
-        eprintln!("Start frame cannot be greater than end frame");
-        process::exit(1);
-    }
-
-    let total_frames = end_frame - start_frame + 1;
+    // Determine the frames to output
+    let frames = match selected_frames(&matches, i64::from(video_info.num_frames)) {
+        Ok(frames) if !frames.is_empty() => frames,
+        Ok(_) => {
+            eprintln!("No frames selected");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
 
     // Set up output writer
     let mut writer = match OutputWriter::new(outfile, matches.get_one::<String>("container")) {
@@ -203,27 +291,108 @@ fn main() {
         }
     };
 
-    // Write container header if needed
-    if let Err(e) = writer.write_header(&video_info) {
+    // Write container header if needed, from the clip and the properties of its first frame
+    let alpha_node = environment.get_output_alpha(output_index);
+    let first_frame = match matches.get_one::<String>("container") {
+        Some(_) => match node.get_frame(frames[0]) {
+            Ok(frame) => Some(frame),
+            Err(e) => {
+                eprintln!("Failed to get frame {}: {}", frames[0], e);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    if let Err(e) = writer.write_header(&video_info, first_frame.as_ref(), alpha_node.is_some()) {
         eprintln!("Failed to write container header: {}", e);
         process::exit(1);
     }
 
     // Set up progress tracking
-    let mut progress = ProgressTracker::new(total_frames, matches.get_flag("progress"));
+    let mut progress = progress_tracker(&matches, frames.len() as i32);
 
     // Process frames concurrently
-    let num_requests = *matches
-        .get_one::<usize>("requests")
-        .unwrap_or(&environment.get_core().info().num_threads);
-    process_frames_concurrent(
-        &node,
-        &mut writer,
-        start_frame,
-        end_frame,
-        num_requests,
-        &mut progress,
-    );
+    process_frames_concurrent(&node, &frames, num_requests, &mut progress, |n, frame| {
+        let alpha = match &alpha_node {
+            Some(alpha_node) if writer.writes_alpha() => Some(
+                alpha_node
+                    .get_frame(n)
+                    .map_err(|e| io::Error::other(e.to_string()))?,
+            ),
+            _ => None,
+        };
+        writer.write_frame(frame, alpha.as_ref())
+    });
+
+    progress.finish();
+
+    if let Err(e) = writer.finish() {
+        eprintln!("Failed to finish output: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Writes the audio samples of the `--start`/`--end` range, interleaved.
+fn write_audio(
+    matches: &ArgMatches,
+    node: &rustsynth::node::Node,
+    audio_info: &AudioInfo,
+    outfile: &str,
+    num_requests: usize,
+) {
+    if matches.contains_id("frames") {
+        eprintln!("--frames only applies to video, use --start and --end to select samples");
+        process::exit(1);
+    }
+    let num_samples = audio_info.num_samples;
+    let resolve = |id: &str, default: i64| {
+        let n = matches.get_one::<i64>(id).copied().unwrap_or(default);
+        let sample = if n < 0 { num_samples + n } else { n };
+        if !(0..num_samples).contains(&sample) {
+            eprintln!(
+                "Sample {} is out of range, the node has {} samples",
+                n, num_samples
+            );
+            process::exit(1);
+        }
+        sample
+    };
+    let start_sample = resolve("start", 0);
+    let end_sample = resolve("end", num_samples - 1);
+
+    if start_sample > end_sample {
+        eprintln!("Start sample cannot be greater than end sample");
+        process::exit(1);
+    }
+
+    let mut writer = match OutputWriter::new(outfile, matches.get_one::<String>("container")) {
+        Ok(writer) => writer,
+        Err(e) => {
+            eprintln!("Failed to create output writer: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Err(e) = writer.write_audio_header(audio_info, end_sample - start_sample + 1) {
+        eprintln!("Failed to write container header: {}", e);
+        process::exit(1);
+    }
+
+    let frames: Vec<i32> = (AudioInfo::frame_of_sample(start_sample)
+        ..=AudioInfo::frame_of_sample(end_sample))
+        .collect();
+    let mut progress = progress_tracker(matches, frames.len() as i32);
+
+    process_frames_concurrent(node, &frames, num_requests, &mut progress, |n, frame| {
+        // Only the first and last frames can be partially in the range
+        let frame_start = i64::from(n) * AudioInfo::FRAME_SAMPLES;
+        let first = start_sample.max(frame_start);
+        let last = end_sample.min(frame_start + i64::from(frame.get_length()) - 1);
+        writer.write_audio_frame(
+            frame,
+            (first - frame_start) as usize,
+            (last - first + 1) as usize,
+        )
+    });
 
     progress.finish();
 
@@ -233,93 +402,123 @@ fn main() {
     }
 }
 
+/// Sets up the `--progress` reporting, on `--progress-fd` when given.
+fn progress_tracker(matches: &ArgMatches, total_frames: i32) -> ProgressTracker {
+    let format = matches
+        .get_one::<String>("progress")
+        .and_then(|name| ProgressFormat::from_name(name));
+    let Some(&fd) = matches.get_one::<i32>("progress-fd") else {
+        return ProgressTracker::new(total_frames, format);
+    };
+    match progress_output(fd) {
+        Ok(out) => ProgressTracker::with_output(total_frames, format, out),
+        Err(e) => {
+            eprintln!("Can't write progress to file descriptor {}: {}", fd, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Opens an inherited file descriptor, or a handle on Windows, for writing.
+fn progress_output(fd: i32) -> io::Result<Box<dyn Write>> {
+    if fd < 0 {
+        return Err(io::Error::from(io::ErrorKind::InvalidInput));
+    }
+    // The descriptor is owned by the process that spawned rspipe, so write to a duplicate
+    #[cfg(unix)]
+    let handle = unsafe { std::os::fd::BorrowedFd::borrow_raw(fd) }.try_clone_to_owned()?;
+    #[cfg(windows)]
+    let handle = unsafe {
+        std::os::windows::io::BorrowedHandle::borrow_raw(
+            fd as isize as std::os::windows::io::RawHandle,
+        )
+    }
+    .try_clone_to_owned()?;
+    Ok(Box::new(std::fs::File::from(handle)))
+}
+
+/// The frames picked by `--frames`, or the `--start`/`--end` range, of a clip with `count` frames.
+fn selected_frames(matches: &ArgMatches, count: i64) -> Result<Vec<i32>, String> {
+    if let Some(selection) = matches.get_one::<String>("frames") {
+        return selection.parse::<frames::Selection>()?.resolve(count);
+    }
+    let start = match matches.get_one::<i64>("start") {
+        Some(&n) => frames::resolve_index(n, count)?,
+        None => 0,
+    };
+    let end = match matches.get_one::<i64>("end") {
+        Some(&n) => frames::resolve_index(n, count)?,
+        None => count - 1,
+    };
+    if start > end {
+        return Err("Start frame cannot be greater than end frame".to_string());
+    }
+    Ok((start as i32..=end as i32).collect())
+}
+
+/// Requests `frames` with up to `num_requests` in flight and passes them to `write_frame` in the
+/// order of the list.
 fn process_frames_concurrent(
     node: &rustsynth::node::Node,
-    writer: &mut OutputWriter,
-    start_frame: i32,
-    end_frame: i32,
+    frames: &[i32],
     num_requests: usize,
     progress: &mut ProgressTracker,
+    mut write_frame: impl FnMut(i32, &rustsynth::frame::Frame) -> io::Result<()>,
 ) {
     use std::sync::mpsc;
 
-    let total_frames = end_frame - start_frame + 1;
-    let (tx, rx) = mpsc::channel::<(i32, Result<rustsynth::frame::Frame, String>)>();
-    let node_clone = node.clone();
-
-    // Track pending requests
-    let pending_requests = Arc::new(Mutex::new(0));
+    // Results are keyed by their position in the list, a frame may be selected more than once
+    let (tx, rx) = mpsc::channel::<(usize, Result<rustsynth::frame::Frame, String>)>();
 
-    // Start initial batch of async frame requests
-    let mut next_request = start_frame;
+    let request = |position: usize| {
+        let tx = tx.clone();
+        node.get_frame_async(frames[position], move |result, _, _| {
+            let result = result.map_err(|e| format!("Frame error: {}", e));
+            // The receiver is gone once a failure has ended the process
+            let _ = tx.send((position, result));
+        });
+    };
 
     // Request initial batch
-    for _ in 0..num_requests.min(total_frames.try_into().unwrap()) {
-        *pending_requests.lock().unwrap() += 1;
-        let tx_clone = tx.clone();
-        let node_clone = node_clone.clone();
-        let pending_clone = Arc::clone(&pending_requests);
-        let frame_num = next_request;
-        next_request += 1;
-
-        node_clone.get_frame_async(frame_num, move |result, n, _| {
-            let result_owned = match result {
-                Ok(frame) => Ok(frame),
-                Err(e) => Err(format!("Frame error: {}", e)),
-            };
-            tx_clone.send((n, result_owned)).unwrap();
-            *pending_clone.lock().unwrap() -= 1;
-        });
+    let mut next_request = num_requests.max(1).min(frames.len());
+    for position in 0..next_request {
+        request(position);
     }
 
     // Collect and write frames in order
     let mut frames_received = HashMap::new();
-    let mut next_frame = start_frame;
-    let mut frames_written = 0;
+    let mut next_position = 0;
+
+    while next_position < frames.len() {
+        let Ok((position, result)) = rx.recv() else {
+            break;
+        };
+        match result {
+            Ok(frame) => {
+                frames_received.insert(position, frame);
+
+                // Request next frame if we haven't requested all frames yet
+                if next_request < frames.len() {
+                    request(next_request);
+                    next_request += 1;
+                }
 
-    while frames_written < total_frames {
-        if let Ok((frame_num, result)) = rx.recv() {
-            match result {
-                Ok(frame) => {
-                    frames_received.insert(frame_num, frame);
-
-                    // Request next frame if we haven't requested all frames yet
-                    if next_request <= end_frame {
-                        *pending_requests.lock().unwrap() += 1;
-                        let tx_clone = tx.clone();
-                        let node_clone = node_clone.clone();
-                        let pending_clone = Arc::clone(&pending_requests);
-                        let frame_num_to_request = next_request;
-                        next_request += 1;
-
-                        node_clone.get_frame_async(frame_num_to_request, move |result, n, _| {
-                            let result_owned = match result {
-                                Ok(frame) => Ok(frame),
-                                Err(e) => Err(format!("Frame error: {}", e)),
-                            };
-                            tx_clone.send((n, result_owned)).unwrap();
-                            *pending_clone.lock().unwrap() -= 1;
-                        });
+                // Write frames in sequential order
+                while let Some(frame) = frames_received.remove(&next_position) {
+                    let n = frames[next_position];
+                    if let Err(e) = write_frame(n, &frame) {
+                        eprintln!("Failed to write frame {}: {}", n, e);
+                        process::exit(1);
                     }
 
-                    // Write frames in sequential order
-                    while let Some(frame) = frames_received.remove(&next_frame) {
-                        if let Err(e) = writer.write_frame(&frame) {
-                            eprintln!("Failed to write frame {}: {}", next_frame, e);
-                            process::exit(1);
-                        }
-
-                        frames_written += 1;
-                        next_frame += 1;
-
-                        progress.update(frames_written);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to get frame {}: {}", frame_num, e);
-                    process::exit(1);
+                    next_position += 1;
+                    progress.update(next_position as i32);
                 }
             }
+            Err(e) => {
+                eprintln!("Failed to get frame {}: {}", frames[position], e);
+                process::exit(1);
+            }
         }
     }
 }
@@ -368,6 +567,18 @@ fn print_node_info(node: &rustsynth::node::Node) {
         )
         .unwrap();
         writeln!(writer, "Num Planes: {}", video_info.format.num_planes).unwrap();
+
+        if video_info.num_frames > 0 {
+            match node.get_frame(0) {
+                Ok(frame) => {
+                    writeln!(writer, "Frame Properties:").unwrap();
+                    for (key, values) in frame.properties().iter() {
+                        writeln!(writer, "  {}: {}", key, values).unwrap();
+                    }
+                }
+                Err(e) => writeln!(writer, "Frame Properties: unavailable ({})", e).unwrap(),
+            }
+        }
     } else if let Some(audio_info) = node.audio_info() {
         writeln!(writer, "Sample Rate: {}", audio_info.sample_rate).unwrap();
         writeln!(writer, "Num Samples: {}", audio_info.num_samples).unwrap();
@@ -404,3 +615,42 @@ fn print_node_info(node: &rustsynth::node::Node) {
 
     writer.flush().unwrap();
 }
+
+/// Re-evaluates the script on every change, printing the info of the selected output or the
+/// error. Never returns.
+fn watch(script_path: &str, output_index: i32, script_args: Vec<(String, args::ScriptArg)>) -> ! {
+    let mut watcher = ScriptWatcher::new(script_path)
+        .with_setup(move |environment| args::set_all(environment, &script_args));
+    loop {
+        match watcher.wait(Duration::from_millis(500)) {
+            Ok(environment) => match environment.get_output(output_index) {
+                Some(node) => {
+                    eprintln!("Evaluated {}", script_path);
+                    print_node_info(&node);
+                }
+                None => eprintln!("No output node found at index {}", output_index),
+            },
+            Err(e) => report_script_error(script_path, &e),
+        }
+        eprintln!("Watching for changes...");
+    }
+}
+
+/// Prints a script error, starting with a `file:line: Exception: message` line pointing into the
+/// script when the traceback has one.
+fn report_script_error(script_path: &str, error: &ScriptError) {
+    let ScriptError::VSScript(vs_error) = error else {
+        eprintln!("Script evaluation failed: {}", error);
+        return;
+    };
+    // The script is evaluated under the path it was given, so its frames carry that path
+    if let Some(traceback) = vs_error.traceback()
+        && let Some(frame) = traceback.frame_in(script_path)
+    {
+        eprintln!(
+            "{}:{}: {}: {}",
+            script_path, frame.line, traceback.exception, traceback.message
+        );
+    }
+    eprintln!("Script evaluation failed:\n{}", vs_error.raw());
+}
@@ -1,38 +1,89 @@
-use rustsynth::format::ColorFamily;
-use rustsynth::{format::VideoInfo, frame::Frame};
+use rustsynth::{
+    format::{AudioInfo, VideoInfo},
+    frame::Frame,
+};
 use std::fs::File;
-use std::io::{self, BufWriter, Write};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+
+use crate::wave::{self, SampleLayout, SizeFields};
+use crate::y4m;
+
+trait Output: Write + Seek {}
+
+impl<T: Write + Seek> Output for T {}
+
+/// The header sizes of an audio container, patched on [`OutputWriter::finish()`].
+struct AudioContainer {
+    w64: bool,
+    sizes: SizeFields,
+}
 
 pub struct OutputWriter {
-    writer: Box<dyn Write>,
+    writer: Box<dyn Output>,
+    /// Where the output started, `None` if it can't seek.
+    start: Option<u64>,
     container_format: Option<String>,
     wrote_header: bool,
+    write_alpha: bool,
+    audio_layout: Option<SampleLayout>,
+    audio_container: Option<AudioContainer>,
+    audio_buffer: Vec<u8>,
+    data_size: u64,
+}
+
+/// Opens stdout as a file, so that it can be seeked when redirected to one.
+fn stdout_file() -> io::Result<File> {
+    #[cfg(unix)]
+    let handle = std::os::fd::AsFd::as_fd(&io::stdout()).try_clone_to_owned()?;
+    #[cfg(windows)]
+    let handle = std::os::windows::io::AsHandle::as_handle(&io::stdout()).try_clone_to_owned()?;
+    Ok(File::from(handle))
 }
 
 impl OutputWriter {
     pub fn new(outfile: &str, container: Option<&String>) -> io::Result<Self> {
-        let writer: Box<dyn Write> = match outfile {
-            "-" => Box::new(BufWriter::with_capacity(1024 * 1024, io::stdout())),
-            "--" => Box::new(io::sink()),
+        let mut writer: Box<dyn Output> = match outfile {
+            "-" => Box::new(BufWriter::with_capacity(1024 * 1024, stdout_file()?)),
+            "--" => Box::new(io::empty()),
             path => Box::new(BufWriter::with_capacity(1024 * 1024, File::create(path)?)),
         };
+        // Pipes can't seek, in which case the header keeps the sizes it was written with
+        let start = writer.stream_position().ok();
 
         Ok(OutputWriter {
             writer,
+            start,
             container_format: container.cloned(),
             wrote_header: false,
+            write_alpha: false,
+            audio_layout: None,
+            audio_container: None,
+            audio_buffer: Vec::new(),
+            data_size: 0,
         })
     }
 
-    pub fn write_header(&mut self, video_info: &VideoInfo) -> io::Result<()> {
+    /// Writes the container header, taking properties such as interlacing from `first_frame`.
+    ///
+    /// With `alpha` the frames are written with their alpha planes when the container has room
+    /// for them.
+    pub fn write_header(
+        &mut self,
+        video_info: &VideoInfo,
+        first_frame: Option<&Frame>,
+        alpha: bool,
+    ) -> io::Result<()> {
         if let Some(container) = &self.container_format {
             match container.as_str() {
-                "y4m" => self.write_y4m_header(video_info)?,
+                "y4m" => {
+                    let header = y4m::header(video_info, first_frame, alpha)?;
+                    self.writer.write_all(header.as_bytes())?;
+                    self.write_alpha = alpha && y4m::supports_alpha(video_info);
+                }
                 "wav" | "w64" => {
-                    // Audio container headers would go here
                     return Err(io::Error::new(
-                        io::ErrorKind::Unsupported,
-                        "Audio containers not yet implemented",
+                        io::ErrorKind::InvalidInput,
+                        format!("{} can only hold audio", container),
                     ));
                 }
                 _ => {
@@ -47,46 +98,19 @@ impl OutputWriter {
         Ok(())
     }
 
-    fn write_y4m_header(&mut self, video_info: &VideoInfo) -> io::Result<()> {
-        // Y4M header format: YUV4MPEG2 W<width> H<height> F<fps_num>:<fps_den> Ip A0:0 C420jpeg XYSCSS=420JPEG
-        let format_tag = match (
-            video_info.format.color_family,
-            video_info.format.bits_per_sample,
-            video_info.format.sub_sampling_w,
-            video_info.format.sub_sampling_h,
-        ) {
-            (ColorFamily::YUV, 8, 1, 1) => "C420jpeg",
-            (ColorFamily::YUV, 8, 1, 0) => "C422",
-            (ColorFamily::YUV, 8, 0, 0) => "C444",
-            (ColorFamily::YUV, 10, 1, 1) => "C420p10",
-            (ColorFamily::YUV, 10, 1, 0) => "C422p10",
-            (ColorFamily::YUV, 10, 0, 0) => "C444p10",
-            (ColorFamily::YUV, 12, 1, 1) => "C420p12",
-            (ColorFamily::YUV, 12, 1, 0) => "C422p12",
-            (ColorFamily::YUV, 12, 0, 0) => "C444p12",
-            (ColorFamily::YUV, 16, 1, 1) => "C420p16",
-            (ColorFamily::YUV, 16, 1, 0) => "C422p16",
-            (ColorFamily::YUV, 16, 0, 0) => "C444p16",
-            _ => "C420jpeg", // default fallback
-        };
-
-        writeln!(
-            self.writer,
-            "YUV4MPEG2 W{} H{} F{}:{} Ip A0:0 {}",
-            video_info.width, video_info.height, video_info.fps_num, video_info.fps_den, format_tag
-        )?;
-
-        Ok(())
+    /// Whether [`OutputWriter::write_frame()`] needs the alpha frames.
+    pub fn writes_alpha(&self) -> bool {
+        self.write_alpha
     }
 
-    pub fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+    pub fn write_frame(&mut self, frame: &Frame, alpha: Option<&Frame>) -> io::Result<()> {
         if let Some(container) = &self.container_format {
             match container.as_str() {
-                "y4m" => self.write_y4m_frame(frame)?,
+                "y4m" => self.write_y4m_frame(frame, alpha)?,
                 "wav" | "w64" => {
                     return Err(io::Error::new(
-                        io::ErrorKind::Unsupported,
-                        "Audio containers not yet implemented",
+                        io::ErrorKind::InvalidInput,
+                        format!("{} can only hold audio", container),
                     ));
                 }
                 _ => {
@@ -102,12 +126,17 @@ impl OutputWriter {
         Ok(())
     }
 
-    fn write_y4m_frame(&mut self, frame: &Frame) -> io::Result<()> {
+    fn write_y4m_frame(&mut self, frame: &Frame, alpha: Option<&Frame>) -> io::Result<()> {
         // Y4M frame header
         writeln!(self.writer, "FRAME")?;
 
         // Write raw frame data
         self.write_raw_frame(frame)?;
+        if self.write_alpha {
+            let alpha = alpha
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing alpha frame"))?;
+            self.write_raw_frame(alpha)?;
+        }
         Ok(())
     }
 
@@ -139,7 +168,109 @@ impl OutputWriter {
         Ok(())
     }
 
+    /// Writes the header of an audio container for `num_samples` samples, if one was requested.
+    pub fn write_audio_header(
+        &mut self,
+        audio_info: &AudioInfo,
+        num_samples: i64,
+    ) -> io::Result<()> {
+        let format = &audio_info.format;
+        let layout = SampleLayout::new(format);
+        self.audio_layout = Some(layout);
+
+        if let Some(container) = &self.container_format {
+            let data_size = num_samples as u64 * layout.block_align() as u64;
+            let (header, sizes, w64) = match container.as_str() {
+                "wav" => {
+                    let (header, sizes) =
+                        wave::wav_header(format, audio_info.sample_rate, data_size)?;
+                    (header, sizes, false)
+                }
+                "w64" => {
+                    let (header, sizes) =
+                        wave::w64_header(format, audio_info.sample_rate, data_size);
+                    (header, sizes, true)
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("{} can't hold audio", container),
+                    ));
+                }
+            };
+            self.writer.write_all(&header)?;
+            self.audio_container = Some(AudioContainer { w64, sizes });
+        }
+        self.wrote_header = true;
+        Ok(())
+    }
+
+    /// Writes `count` samples of every channel of an audio frame, starting at `offset`.
+    pub fn write_audio_frame(
+        &mut self,
+        frame: &Frame,
+        offset: usize,
+        count: usize,
+    ) -> io::Result<()> {
+        let layout = self.audio_layout.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Audio header wasn't written")
+        })?;
+        let length = frame.get_length() as usize;
+        if offset + count > length {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Audio frame is shorter than expected",
+            ));
+        }
+
+        let channels: Vec<&[u8]> = (0..layout.channels as i32)
+            .map(|channel| unsafe {
+                std::slice::from_raw_parts(frame.get_read_ptr(channel), length * layout.bytes_in)
+            })
+            .collect();
+        self.audio_buffer.clear();
+        wave::interleave(&channels, layout, offset, count, &mut self.audio_buffer);
+        self.writer.write_all(&self.audio_buffer)?;
+        self.data_size += self.audio_buffer.len() as u64;
+        Ok(())
+    }
+
+    /// Pads the audio data and patches the container sizes with the amount actually written.
+    fn finish_audio_container(&mut self) -> io::Result<()> {
+        let Some(container) = &self.audio_container else {
+            return Ok(());
+        };
+        let data_size = self.data_size;
+        let padding = if container.w64 {
+            data_size.next_multiple_of(8) - data_size
+        } else {
+            data_size % 2
+        };
+        self.writer.write_all(&vec![0; padding as usize])?;
+
+        let Some(start) = self.start else {
+            return Ok(());
+        };
+        let end = self.writer.stream_position()?;
+        let (riff, data) = (container.sizes.riff, container.sizes.data);
+        if container.w64 {
+            self.patch(start + riff, &wave::w64_riff_size(data_size).to_le_bytes())?;
+            self.patch(start + data, &wave::w64_data_size(data_size).to_le_bytes())?;
+        } else if let Some((riff_size, data_chunk_size)) = wave::wav_sizes(data_size) {
+            self.patch(start + riff, &riff_size.to_le_bytes())?;
+            self.patch(start + data, &data_chunk_size.to_le_bytes())?;
+        }
+        self.writer.seek(SeekFrom::Start(end))?;
+        Ok(())
+    }
+
+    fn patch(&mut self, position: u64, bytes: &[u8]) -> io::Result<()> {
+        self.writer.seek(SeekFrom::Start(position))?;
+        self.writer.write_all(bytes)
+    }
+
     pub fn finish(mut self) -> io::Result<()> {
+        self.finish_audio_container()?;
         self.writer.flush()?;
         Ok(())
     }
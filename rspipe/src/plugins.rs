@@ -0,0 +1,159 @@
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use rustsynth::core::{CoreCreationFlags, CoreRef};
+use rustsynth::plugin::Plugin;
+use std::process;
+
+use crate::json;
+
+pub fn command() -> Command {
+    Command::new("plugins")
+        .about("List the installed plugins and their versions")
+        .arg(
+            Arg::new("namespace")
+                .short('n')
+                .long("namespace")
+                .help("Only list the plugin with this namespace (repeatable)")
+                .value_name("NAMESPACE")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("functions")
+                .short('f')
+                .long("functions")
+                .help("Also list the functions of each plugin with their signatures")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print JSON instead of text")
+                .action(ArgAction::SetTrue),
+        )
+}
+
+struct PluginInfo {
+    namespace: String,
+    id: Option<String>,
+    name: Option<String>,
+    version: i32,
+    path: Option<String>,
+    functions: Vec<FunctionInfo>,
+}
+
+struct FunctionInfo {
+    name: String,
+    arguments: String,
+    returns: String,
+}
+
+impl PluginInfo {
+    fn from_plugin(plugin: &Plugin<'_>, with_functions: bool) -> Option<Self> {
+        let mut functions = Vec::new();
+        if with_functions {
+            functions = plugin
+                .functions()
+                .filter_map(|function| {
+                    Some(FunctionInfo {
+                        name: function.get_name()?,
+                        arguments: function.get_arguments().unwrap_or_default(),
+                        returns: function.get_return_type().unwrap_or_default(),
+                    })
+                })
+                .collect();
+            functions.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Some(Self {
+            namespace: plugin.namespace()?,
+            id: plugin.id(),
+            name: plugin.name(),
+            version: plugin.version(),
+            path: plugin.path(),
+            functions,
+        })
+    }
+
+    /// The version as `major.minor`, as packed by `VS_MAKE_VERSION`.
+    fn version_string(&self) -> String {
+        format!("{}.{}", self.version >> 16, self.version & 0xffff)
+    }
+
+    fn print_text(&self) {
+        println!(
+            "{} ({}) {}: {}",
+            self.namespace,
+            self.id.as_deref().unwrap_or("?"),
+            self.version_string(),
+            self.name.as_deref().unwrap_or("")
+        );
+        println!("    path: {}", self.path.as_deref().unwrap_or("(built in)"));
+        for function in &self.functions {
+            println!(
+                "    {}({}) -> {}",
+                function.name, function.arguments, function.returns
+            );
+        }
+    }
+
+    fn to_json(&self, with_functions: bool) -> String {
+        let mut out = format!(
+            "{{\"namespace\":{},\"id\":{},\"name\":{},\"version\":{},\"path\":{}",
+            json::string(&self.namespace),
+            json::optional_string(self.id.as_deref()),
+            json::optional_string(self.name.as_deref()),
+            json::string(&self.version_string()),
+            json::optional_string(self.path.as_deref())
+        );
+        if with_functions {
+            let functions: Vec<_> = self
+                .functions
+                .iter()
+                .map(|function| {
+                    format!(
+                        "{{\"name\":{},\"arguments\":{},\"returns\":{}}}",
+                        json::string(&function.name),
+                        json::string(&function.arguments),
+                        json::string(&function.returns)
+                    )
+                })
+                .collect();
+            out.push_str(&format!(",\"functions\":[{}]", functions.join(",")));
+        }
+        out.push('}');
+        out
+    }
+}
+
+pub fn run(matches: &ArgMatches) {
+    let core = CoreRef::new(CoreCreationFlags::NONE);
+    let with_functions = matches.get_flag("functions");
+
+    let mut plugins: Vec<_> = match matches.get_many::<String>("namespace") {
+        Some(namespaces) => namespaces
+            .map(|namespace| match core.plugin_by_namespace(namespace) {
+                Some(plugin) => plugin,
+                None => {
+                    eprintln!("No plugin found with namespace {}", namespace);
+                    process::exit(1);
+                }
+            })
+            .filter_map(|plugin| PluginInfo::from_plugin(&plugin, with_functions))
+            .collect(),
+        None => core
+            .plugins()
+            .filter_map(|plugin| PluginInfo::from_plugin(&plugin, with_functions))
+            .collect(),
+    };
+    plugins.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+
+    if matches.get_flag("json") {
+        let entries: Vec<_> = plugins
+            .iter()
+            .map(|plugin| plugin.to_json(with_functions))
+            .collect();
+        println!("[{}]", entries.join(","));
+    } else {
+        for plugin in &plugins {
+            plugin.print_text();
+        }
+    }
+}
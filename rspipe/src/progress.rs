@@ -1,21 +1,54 @@
 use std::io::{self, Write};
 use std::time::Instant;
 
+use crate::json;
+
+/// How progress is reported.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// A line rewritten in place.
+    Text,
+    /// One JSON event per line.
+    Json,
+}
+
+impl ProgressFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
 pub struct ProgressTracker {
     total_frames: i32,
     start_time: Instant,
     last_update: Instant,
-    verbose: bool,
+    format: Option<ProgressFormat>,
+    out: Box<dyn Write>,
 }
 
 impl ProgressTracker {
-    pub fn new(total_frames: i32, verbose: bool) -> Self {
+    /// Reports to stderr in `format`, or only the summary without one.
+    pub fn new(total_frames: i32, format: Option<ProgressFormat>) -> Self {
+        Self::with_output(total_frames, format, Box::new(io::stderr()))
+    }
+
+    /// Reports to `out`, for example a file descriptor set up by the caller.
+    pub fn with_output(
+        total_frames: i32,
+        format: Option<ProgressFormat>,
+        out: Box<dyn Write>,
+    ) -> Self {
         let now = Instant::now();
         ProgressTracker {
             total_frames,
             start_time: now,
             last_update: now,
-            verbose,
+            format,
+            out,
         }
     }
 
@@ -40,27 +73,56 @@ impl ProgressTracker {
             0.0
         };
 
-        if self.verbose {
-            eprint!(
+        // Progress is best effort, a closed pipe must not stop the output
+        let _ = match self.format {
+            Some(ProgressFormat::Text) => write!(
+                self.out,
                 "\rFrame {} of {} ({}%) {:.2} fps, eta {:.0}s",
                 completed_frames,
                 self.total_frames,
                 (progress * 100.0) as u32,
                 fps,
                 eta
-            );
-
-            io::stderr().flush().unwrap();
-        }
+            ),
+            Some(ProgressFormat::Json) => writeln!(
+                self.out,
+                "{}",
+                json::object([
+                    ("event", json::string("progress")),
+                    ("frame", completed_frames.to_string()),
+                    ("total", self.total_frames.to_string()),
+                    ("fps", json::number(fps)),
+                    ("eta", json::number(eta)),
+                    ("elapsed", json::number(elapsed)),
+                ])
+            ),
+            None => return,
+        };
+        let _ = self.out.flush();
     }
 
-    pub fn finish(&self) {
+    pub fn finish(&mut self) {
         let elapsed = self.start_time.elapsed().as_secs_f64();
         let fps = self.total_frames as f64 / elapsed;
 
-        eprintln!(
-            "\rProcessed {} frames in {:.2}s ({:.2} fps)",
-            self.total_frames, elapsed, fps
-        );
+        let _ = if self.format == Some(ProgressFormat::Json) {
+            writeln!(
+                self.out,
+                "{}",
+                json::object([
+                    ("event", json::string("finish")),
+                    ("frames", self.total_frames.to_string()),
+                    ("fps", json::number(fps)),
+                    ("elapsed", json::number(elapsed)),
+                ])
+            )
+        } else {
+            writeln!(
+                self.out,
+                "\rProcessed {} frames in {:.2}s ({:.2} fps)",
+                self.total_frames, elapsed, fps
+            )
+        };
+        let _ = self.out.flush();
     }
 }
@@ -0,0 +1,276 @@
+use rustsynth::format::{AudioFormat, SampleType};
+use std::io;
+
+/// `WAVE_FORMAT_EXTENSIBLE`.
+const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// The tail shared by the `KSDATAFORMAT_SUBTYPE_*` GUIDs, after the format tag.
+const SUBTYPE_SUFFIX: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+];
+
+/// The tail of the W64 chunk GUIDs after their four character code, except `riff`.
+const W64_SUFFIX: [u8; 12] = [
+    0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a,
+];
+
+const W64_RIFF: [u8; 16] = [
+    0x72, 0x69, 0x66, 0x66, 0x2e, 0x91, 0xcf, 0x11, 0xa5, 0xd6, 0x28, 0xdb, 0x04, 0xc1, 0x00, 0x00,
+];
+
+/// The size of the `fmt ` chunk body of a `WAVE_FORMAT_EXTENSIBLE` header.
+const FMT_SIZE: u16 = 40;
+
+/// Where the sizes are in a header, so they can be patched once the data size is known.
+pub struct SizeFields {
+    pub riff: u64,
+    pub data: u64,
+}
+
+/// The container layout of the samples of an audio format.
+#[derive(Clone, Copy)]
+pub struct SampleLayout {
+    pub channels: usize,
+    /// Bytes per sample in the `VapourSynth` frame.
+    pub bytes_in: usize,
+    /// Bytes per sample in the output, 24 bit samples are packed into 3 bytes.
+    pub bytes_out: usize,
+}
+
+impl SampleLayout {
+    pub fn new(format: &AudioFormat) -> Self {
+        Self {
+            channels: format.num_channels as usize,
+            bytes_in: format.bytes_per_sample as usize,
+            bytes_out: (format.bits_per_sample as usize).div_ceil(8),
+        }
+    }
+
+    pub fn block_align(&self) -> usize {
+        self.channels * self.bytes_out
+    }
+}
+
+/// Interleaves `count` samples of every channel starting at `offset`, appending them to `out`.
+///
+/// Samples that are stored wider than they are written, 24 bit integers in 32 bits, keep their
+/// least significant bytes, which is where `VapourSynth` stores the value.
+pub fn interleave(
+    channels: &[&[u8]],
+    layout: SampleLayout,
+    offset: usize,
+    count: usize,
+    out: &mut Vec<u8>,
+) {
+    out.reserve(count * layout.block_align());
+    for sample in offset..offset + count {
+        let start = sample * layout.bytes_in;
+        for channel in channels {
+            out.extend_from_slice(&channel[start..start + layout.bytes_out]);
+        }
+    }
+}
+
+fn fmt_body(format: &AudioFormat, sample_rate: i32) -> Vec<u8> {
+    let layout = SampleLayout::new(format);
+    let block_align = layout.block_align() as u16;
+    // Layouts with channels that have no speaker position are written without positions
+    let channel_mask = format.channel_layout.wave_channel_mask().unwrap_or(0);
+    let format_tag: u8 = match format.sample_type {
+        SampleType::Integer => 1,
+        SampleType::Float => 3,
+    };
+
+    let mut body = Vec::with_capacity(FMT_SIZE as usize);
+    body.extend_from_slice(&FORMAT_EXTENSIBLE.to_le_bytes());
+    body.extend_from_slice(&(layout.channels as u16).to_le_bytes());
+    body.extend_from_slice(&(sample_rate as u32).to_le_bytes());
+    body.extend_from_slice(&(sample_rate as u32 * u32::from(block_align)).to_le_bytes());
+    body.extend_from_slice(&block_align.to_le_bytes());
+    body.extend_from_slice(&(layout.bytes_out as u16 * 8).to_le_bytes());
+    body.extend_from_slice(&(FMT_SIZE - 18).to_le_bytes());
+    body.extend_from_slice(&(format.bits_per_sample as u16).to_le_bytes());
+    body.extend_from_slice(&channel_mask.to_le_bytes());
+    body.extend_from_slice(&[format_tag, 0]);
+    body.extend_from_slice(&SUBTYPE_SUFFIX);
+    body
+}
+
+/// The RIFF size of a WAV file with `data_size` bytes of samples.
+fn wav_riff_size(data_size: u64) -> u64 {
+    4 + 8 + u64::from(FMT_SIZE) + 8 + data_size + data_size % 2
+}
+
+/// Builds a `WAVE_FORMAT_EXTENSIBLE` header for `data_size` bytes of samples.
+pub fn wav_header(
+    format: &AudioFormat,
+    sample_rate: i32,
+    data_size: u64,
+) -> io::Result<(Vec<u8>, SizeFields)> {
+    let riff_size = u32::try_from(wav_riff_size(data_size)).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "WAV files can't hold more than 4 GiB, use -c w64",
+        )
+    })?;
+
+    let mut header = Vec::with_capacity(68);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_size.to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&u32::from(FMT_SIZE).to_le_bytes());
+    header.extend_from_slice(&fmt_body(format, sample_rate));
+    header.extend_from_slice(b"data");
+    let data = header.len() as u64;
+    header.extend_from_slice(&(data_size as u32).to_le_bytes());
+    Ok((header, SizeFields { riff: 4, data }))
+}
+
+/// The sizes to write into a WAV header once `data_size` bytes of samples were written.
+pub fn wav_sizes(data_size: u64) -> Option<(u32, u32)> {
+    Some((
+        u32::try_from(wav_riff_size(data_size)).ok()?,
+        u32::try_from(data_size).ok()?,
+    ))
+}
+
+fn w64_guid(fourcc: &[u8; 4]) -> [u8; 16] {
+    let mut guid = [0; 16];
+    guid[..4].copy_from_slice(fourcc);
+    guid[4..].copy_from_slice(&W64_SUFFIX);
+    guid
+}
+
+/// The size of a W64 header, which every chunk size includes.
+const W64_CHUNK_HEADER: u64 = 24;
+
+/// The RIFF size of a W64 file with `data_size` bytes of samples, chunks are 8 byte aligned.
+pub fn w64_riff_size(data_size: u64) -> u64 {
+    W64_CHUNK_HEADER
+        + 16
+        + W64_CHUNK_HEADER
+        + u64::from(FMT_SIZE)
+        + W64_CHUNK_HEADER
+        + data_size.next_multiple_of(8)
+}
+
+/// The size of the data chunk of a W64 file with `data_size` bytes of samples.
+pub fn w64_data_size(data_size: u64) -> u64 {
+    W64_CHUNK_HEADER + data_size
+}
+
+/// Builds a Sony Wave64 header for `data_size` bytes of samples.
+pub fn w64_header(format: &AudioFormat, sample_rate: i32, data_size: u64) -> (Vec<u8>, SizeFields) {
+    let mut header = Vec::with_capacity(128);
+    header.extend_from_slice(&W64_RIFF);
+    header.extend_from_slice(&w64_riff_size(data_size).to_le_bytes());
+    header.extend_from_slice(&w64_guid(b"wave"));
+    header.extend_from_slice(&w64_guid(b"fmt "));
+    header.extend_from_slice(&(W64_CHUNK_HEADER + u64::from(FMT_SIZE)).to_le_bytes());
+    header.extend_from_slice(&fmt_body(format, sample_rate));
+    header.extend_from_slice(&w64_guid(b"data"));
+    let data = header.len() as u64;
+    header.extend_from_slice(&w64_data_size(data_size).to_le_bytes());
+    (header, SizeFields { riff: 16, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustsynth::format::ChannelLayout;
+
+    const STEREO24: AudioFormat = AudioFormat {
+        sample_type: SampleType::Integer,
+        bits_per_sample: 24,
+        bytes_per_sample: 4,
+        num_channels: 2,
+        channel_layout: ChannelLayout::STEREO,
+    };
+
+    /// The `fmt ` chunk body of 48 kHz `STEREO24`.
+    const STEREO24_FMT: [u8; 40] = [
+        0xfe, 0xff, 0x02, 0x00, 0x80, 0xbb, 0x00, 0x00, 0x00, 0x65, 0x04, 0x00, 0x06, 0x00, 0x18,
+        0x00, 0x16, 0x00, 0x18, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+    ];
+
+    #[test]
+    fn test_interleave_24_bit() {
+        // 0x123456, 0x654321 and -2 on the left, 0x0a0b0c, 0 and 1 on the right
+        let left = [
+            0x56, 0x34, 0x12, 0x00, 0x21, 0x43, 0x65, 0x00, 0xfe, 0xff, 0xff, 0xff,
+        ];
+        let right = [
+            0x0c, 0x0b, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        ];
+        let layout = SampleLayout::new(&STEREO24);
+        assert_eq!(layout.block_align(), 6);
+
+        let mut out = Vec::new();
+        interleave(&[&left, &right], layout, 1, 2, &mut out);
+        assert_eq!(
+            out,
+            [
+                0x21, 0x43, 0x65, 0x00, 0x00, 0x00, 0xfe, 0xff, 0xff, 0x01, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interleave_16_bit() {
+        let left = [0x01, 0x02, 0x03, 0x04];
+        let right = [0x05, 0x06, 0x07, 0x08];
+        let layout = SampleLayout::new(&AudioFormat::STEREO16);
+
+        let mut out = vec![0xff];
+        interleave(&[&left, &right], layout, 0, 2, &mut out);
+        assert_eq!(out, [0xff, 0x01, 0x02, 0x05, 0x06, 0x03, 0x04, 0x07, 0x08]);
+    }
+
+    #[test]
+    fn test_wav_header() {
+        let (header, sizes) = wav_header(&STEREO24, 48000, 600).unwrap();
+        let mut expected = Vec::new();
+        expected.extend_from_slice(b"RIFF");
+        expected.extend_from_slice(&[0x94, 0x02, 0x00, 0x00]);
+        expected.extend_from_slice(b"WAVEfmt ");
+        expected.extend_from_slice(&[0x28, 0x00, 0x00, 0x00]);
+        expected.extend_from_slice(&STEREO24_FMT);
+        expected.extend_from_slice(b"data");
+        expected.extend_from_slice(&[0x58, 0x02, 0x00, 0x00]);
+        assert_eq!(header, expected);
+        assert_eq!((sizes.riff, sizes.data), (4, 64));
+
+        // The data chunk is padded to an even size
+        assert_eq!(wav_sizes(601), Some((662, 601)));
+        assert_eq!(wav_sizes(u64::from(u32::MAX)), None);
+        assert!(wav_header(&STEREO24, 48000, u64::from(u32::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_w64_header() {
+        let (header, sizes) = w64_header(&STEREO24, 48000, 601);
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&[
+            0x72, 0x69, 0x66, 0x66, 0x2e, 0x91, 0xcf, 0x11, 0xa5, 0xd6, 0x28, 0xdb, 0x04, 0xc1,
+            0x00, 0x00,
+        ]);
+        expected.extend_from_slice(&[0xe0, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        for fourcc in [b"wave", b"fmt "] {
+            expected.extend_from_slice(fourcc);
+            expected.extend_from_slice(&[
+                0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a,
+            ]);
+        }
+        expected.extend_from_slice(&[0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        expected.extend_from_slice(&STEREO24_FMT);
+        expected.extend_from_slice(b"data");
+        expected.extend_from_slice(&[
+            0xf3, 0xac, 0xd3, 0x11, 0x8c, 0xd1, 0x00, 0xc0, 0x4f, 0x8e, 0xdb, 0x8a,
+        ]);
+        expected.extend_from_slice(&[0x71, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(header, expected);
+        assert_eq!((sizes.riff, sizes.data), (16, 120));
+    }
+}
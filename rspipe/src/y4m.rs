@@ -0,0 +1,162 @@
+use rustsynth::{
+    format::{ColorFamily, SampleType, VideoInfo},
+    frame::{ChromaLocation, ColorRange, FieldBased, Frame},
+};
+use std::io;
+
+/// Whether an alpha plane can be written along with the format, which Y4M only has for 8 bit
+/// 4:4:4.
+pub fn supports_alpha(video_info: &VideoInfo) -> bool {
+    let format = &video_info.format;
+    format.color_family == ColorFamily::YUV
+        && format.sample_type == SampleType::Integer
+        && format.bits_per_sample == 8
+        && format.sub_sampling_w == 0
+        && format.sub_sampling_h == 0
+}
+
+/// The colorspace tag, picking the 4:2:0 chroma siting from `_ChromaLocation`.
+fn colorspace(
+    video_info: &VideoInfo,
+    first_frame: Option<&Frame>,
+    alpha: bool,
+) -> io::Result<&'static str> {
+    if alpha && supports_alpha(video_info) {
+        return Ok("444alpha");
+    }
+    let colorspace = video_info.format.y4m_colorspace().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Y4M can't hold the output format: {}", e),
+        )
+    })?;
+    if colorspace != "420jpeg" {
+        return Ok(colorspace);
+    }
+    Ok(match first_frame.and_then(Frame::chroma_location) {
+        Some(ChromaLocation::Left) => "420mpeg2",
+        Some(ChromaLocation::TopLeft) => "420paldv",
+        _ => "420jpeg",
+    })
+}
+
+/// Builds the stream header from the clip and the properties of its first frame.
+pub fn header(
+    video_info: &VideoInfo,
+    first_frame: Option<&Frame>,
+    alpha: bool,
+) -> io::Result<String> {
+    let colorspace = colorspace(video_info, first_frame, alpha)?;
+    let interlacing = match first_frame.and_then(Frame::field_based) {
+        Some(FieldBased::TopFieldFirst) => 't',
+        Some(FieldBased::BottomFieldFirst) => 'b',
+        Some(FieldBased::Progressive) | None => 'p',
+    };
+    let (sar_num, sar_den) = first_frame
+        .and_then(Frame::sample_aspect_ratio)
+        .filter(|&(num, den)| num > 0 && den > 0)
+        .unwrap_or((0, 0));
+
+    let mut header = format!(
+        "YUV4MPEG2 W{} H{} F{}:{} I{} A{}:{} C{}",
+        video_info.width,
+        video_info.height,
+        video_info.fps_num,
+        video_info.fps_den,
+        interlacing,
+        sar_num,
+        sar_den,
+        colorspace
+    );
+    match first_frame.and_then(Frame::color_range) {
+        Some(ColorRange::Full) => header.push_str(" XCOLORRANGE=FULL"),
+        Some(ColorRange::Limited) => header.push_str(" XCOLORRANGE=LIMITED"),
+        None => {}
+    }
+    header.push('\n');
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustsynth::core::{CoreCreationFlags, CoreRef};
+    use rustsynth::format::PresetVideoFormat;
+
+    fn video_info(format: PresetVideoFormat) -> VideoInfo {
+        VideoInfo {
+            format: format.into(),
+            fps_num: 24000,
+            fps_den: 1001,
+            width: 640,
+            height: 480,
+            num_frames: 10,
+        }
+    }
+
+    #[test]
+    fn test_header_without_frame() {
+        assert_eq!(
+            header(&video_info(PresetVideoFormat::YUV420P8), None, false).unwrap(),
+            "YUV4MPEG2 W640 H480 F24000:1001 Ip A0:0 C420jpeg\n"
+        );
+        assert_eq!(
+            header(&video_info(PresetVideoFormat::YUV422P10), None, true).unwrap(),
+            "YUV4MPEG2 W640 H480 F24000:1001 Ip A0:0 C422p10\n"
+        );
+    }
+
+    #[test]
+    fn test_alpha() {
+        let yuv444 = video_info(PresetVideoFormat::YUV444P8);
+        assert!(supports_alpha(&yuv444));
+        assert!(!supports_alpha(&video_info(PresetVideoFormat::YUV444P16)));
+        assert!(!supports_alpha(&video_info(PresetVideoFormat::YUV420P8)));
+        assert_eq!(colorspace(&yuv444, None, true).unwrap(), "444alpha");
+        assert_eq!(colorspace(&yuv444, None, false).unwrap(), "444");
+    }
+
+    #[test]
+    fn test_rgb_is_unsupported() {
+        let error = header(&video_info(PresetVideoFormat::RGB24), None, false).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(
+            error
+                .to_string()
+                .starts_with("Y4M can't hold the output format")
+        );
+    }
+
+    #[test]
+    fn test_header_from_frame_properties() {
+        let core = CoreRef::new(CoreCreationFlags::NONE);
+        let info = video_info(PresetVideoFormat::YUV420P8);
+        let mut frame = Frame::new_video_frame(&core, 640, 480, &info.format, None);
+        frame.set_field_based(FieldBased::TopFieldFirst).unwrap();
+        frame.set_sample_aspect_ratio(10, 11).unwrap();
+        frame.set_color_range(ColorRange::Limited).unwrap();
+        frame.set_chroma_location(ChromaLocation::Left).unwrap();
+        assert_eq!(
+            header(&info, Some(&frame), false).unwrap(),
+            "YUV4MPEG2 W640 H480 F24000:1001 It A10:11 C420mpeg2 XCOLORRANGE=LIMITED\n"
+        );
+
+        frame.set_field_based(FieldBased::BottomFieldFirst).unwrap();
+        frame.set_color_range(ColorRange::Full).unwrap();
+        frame.set_chroma_location(ChromaLocation::TopLeft).unwrap();
+        assert_eq!(
+            header(&info, Some(&frame), false).unwrap(),
+            "YUV4MPEG2 W640 H480 F24000:1001 Ib A10:11 C420paldv XCOLORRANGE=FULL\n"
+        );
+
+        // An unknown aspect ratio is 0:0, and other sitings keep the default tag
+        frame.set_sample_aspect_ratio(0, 1).unwrap();
+        frame.set_chroma_location(ChromaLocation::Center).unwrap();
+        assert_eq!(colorspace(&info, Some(&frame), false).unwrap(), "420jpeg");
+        assert!(
+            header(&info, Some(&frame), false)
+                .unwrap()
+                .contains(" A0:0 ")
+        );
+    }
+}
@@ -1,65 +1,91 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{self, parse_macro_input, DeriveInput, Ident, ItemMod};
+use syn::{self, parse_macro_input, DeriveInput, ItemMod};
+
+mod map;
 
 /// Derive macro generating an impl of `rustsynth::map::IntoOwnedMap`.
 ///
-/// This macro automatically generates an implementation that converts a struct
-/// into a VapourSynth map by storing each field as a map entry.
+/// Each named field is stored under a key of the same name. Field types must implement
+/// `rustsynth::map::Value`; `Option<T>` fields are only stored when they are `Some`, and `Vec<T>`
+/// fields append every element under the same key.
+///
+/// Field attributes:
+/// - `#[map(rename = "key")]` stores the field under a different key,
+/// - `#[map(skip)]` leaves the field out of the map.
+///
+/// A struct with a lifetime parameter converts into a map with that lifetime, so `Node`, `Frame`
+/// and `Function` fields can be stored.
 ///
 /// # Example
-/// ```
+/// ```ignore
+/// use rustsynth::map::IntoOwnedMap;
 /// use rustsynth::IntoOwnedMap;
 ///
 /// #[derive(IntoOwnedMap)]
 /// struct MyStruct {
-///    field1: i32,
+///     field1: i64,
+///     #[map(rename = "name")]
 ///     field2: String,
+///     planes: Vec<i64>,
 /// }
-/// let s = MyStruct { field1: 42, field2: "Hello".to_string() };
-/// let map = s.into_owned_map();
-/// assert_eq!(map.get::<i32>("field1").unwrap(), &42);
-/// assert_eq!(map.get::<String>("field2").unwrap(), &"Hello".to_string());
+/// let s = MyStruct { field1: 42, field2: "Hello".to_string(), planes: vec![0, 1] };
+/// let map = s.into_owned_map().unwrap();
+/// assert_eq!(map.get::<i64>("field1").unwrap(), 42);
+/// assert_eq!(map.get::<String>("name").unwrap(), "Hello");
+/// assert_eq!(map.value_count("planes").unwrap(), 2);
 /// ```
-#[proc_macro_derive(IntoOwnedMap)]
+#[proc_macro_derive(IntoOwnedMap, attributes(map))]
 pub fn into_owned_map_derive(input: TokenStream) -> TokenStream {
-    // Construct a representation of Rust code as a syntax tree
-    // that we can manipulate
-    let ast = syn::parse(input).unwrap();
-
-    // Build the From implementation
-    impl_map_macro(&ast)
+    let input = parse_macro_input!(input as DeriveInput);
+    map::impl_into_owned_map(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
-fn impl_map_macro(ast: &syn::DeriveInput) -> TokenStream {
-    let name = &ast.ident;
-    let fields: Vec<Ident> = match &ast.data {
-        syn::Data::Struct(ds) => match &ds.fields {
-            syn::Fields::Named(named) => named
-                .named
-                .iter()
-                .map(|x| x.ident.clone().unwrap())
-                .collect(),
-            _ => panic!("Must have named fields"),
-        },
-        _ => panic!("Must be a data struct"),
-    };
-    let gen = quote! {
-        impl rustsynth::map::IntoOwnedMap for #name {
-            fn into_owned_map<'elem>(self) -> rustsynth::map::Map<'elem> {
-                // Ensure API is initialized before creating a map
-                // This is required for Map::new() to work correctly
-                let mut map = rustsynth::map::Map::new()
-                    .expect("Failed to create map - ensure VapourSynth API is initialized with init_api() or use within a VapourSynth plugin context");
-                #(
-                    map.set(stringify!(#fields), &self.#fields)
-                        .expect(&format!("Failed to set field '{}' in map", stringify!(#fields)));
-                )*
-                map
-            }
-        }
-    };
-    gen.into()
+/// Derive macro generating an impl of `rustsynth::map::FromMap`.
+///
+/// Each named field is read from the key of the same name. A missing key is an error
+/// (`MapError::MissingField`, naming the key and the field) unless the field is an `Option<T>`,
+/// which becomes `None`, a `Vec<T>`, which becomes empty, or has a default. `Vec<T>` fields
+/// collect every element of the key.
+///
+/// Field attributes:
+/// - `#[map(rename = "key")]` reads the field from a different key,
+/// - `#[map(default)]` uses `Default::default()` when the key is missing,
+/// - `#[map(default = "path")]` calls the given function when the key is missing,
+/// - `#[map(skip)]` never reads the field and uses its default instead.
+///
+/// # Example
+/// ```ignore
+/// use rustsynth::map::{FromMap, MapRef};
+/// use rustsynth::node::Node;
+/// use rustsynth::FromMap;
+///
+/// #[derive(FromMap)]
+/// struct BlurArgs<'core> {
+///     clip: Node<'core>,
+///     #[map(default = "default_radius")]
+///     radius: i64,
+///     planes: Vec<i64>,
+///     #[map(rename = "mode")]
+///     blur_mode: Option<String>,
+/// }
+///
+/// fn default_radius() -> i64 {
+///     1
+/// }
+///
+/// fn parse<'core>(args: &MapRef<'core>) -> rustsynth::map::MapResult<BlurArgs<'core>> {
+///     BlurArgs::from_map(args)
+/// }
+/// ```
+#[proc_macro_derive(FromMap, attributes(map))]
+pub fn from_map_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    map::impl_from_map(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 /// Macro to define a VapourSynth plugin containing multiple filters
@@ -402,6 +428,12 @@ fn generate_vs_filter(
                 plugin: *mut rustsynth::ffi::VSPlugin,
                 vspapi: *const rustsynth::ffi::VSPLUGINAPI
             ) {
+                const {
+                    assert!(
+                        rustsynth::signature::is_valid(Self::ARGS),
+                        "Filter::ARGS is not a valid argument signature"
+                    )
+                };
                 unsafe {
                     let api = &*vspapi;
                     let filter_name = std::ffi::CString::new(Self::NAME).unwrap();
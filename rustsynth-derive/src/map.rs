@@ -0,0 +1,312 @@
+//! Code generation for the `IntoOwnedMap` and `FromMap` derives.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    DeriveInput, Field, GenericArgument, GenericParam, Generics, Lifetime, LifetimeParam, LitStr,
+    PathArguments, Type,
+};
+
+/// How a field is stored in the map.
+enum FieldKind<'a> {
+    /// A single value, the key must be present.
+    Single,
+    /// `Option<T>`, the key may be missing.
+    Optional(&'a Type),
+    /// `Vec<T>`, every element of the key.
+    Multi(&'a Type),
+    /// `Option<Vec<T>>`, every element of the key if it is present.
+    OptionalMulti(&'a Type),
+}
+
+/// What to do with a key missing from the map.
+enum DefaultValue {
+    None,
+    Trait,
+    Path(syn::ExprPath),
+}
+
+struct MapField<'a> {
+    field: &'a Field,
+    key: String,
+    skip: bool,
+    default: DefaultValue,
+    kind: FieldKind<'a>,
+}
+
+/// Returns the type argument of `ty` if it is a path ending in `wrapper` with a single argument.
+fn unwrap_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// Checks `key` against the rules of `MapRef::is_key_valid`.
+fn is_key_valid(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl<'a> MapField<'a> {
+    fn parse(field: &'a Field) -> syn::Result<Self> {
+        let ident = field.ident.as_ref().expect("named field");
+        let mut key = ident.to_string();
+        let mut key_span = ident.span();
+        let mut skip = false;
+        let mut default = DefaultValue::None;
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("map")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    key = lit.value();
+                    key_span = lit.span();
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    default = if meta.input.peek(syn::Token![=]) {
+                        let lit: LitStr = meta.value()?.parse()?;
+                        DefaultValue::Path(lit.parse()?)
+                    } else {
+                        DefaultValue::Trait
+                    };
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename = \"...\"`, `skip` or `default`"))
+                }
+            })?;
+        }
+
+        let key = key.strip_prefix("r#").map(str::to_owned).unwrap_or(key);
+        if !skip && !is_key_valid(&key) {
+            return Err(syn::Error::new(
+                key_span,
+                format!(
+                    "`{key}` is not a valid map key: keys must start with a letter or underscore \
+                     and contain only alphanumeric characters and underscores"
+                ),
+            ));
+        }
+
+        let kind = if let Some(inner) = unwrap_type(&field.ty, "Option") {
+            match unwrap_type(inner, "Vec") {
+                Some(elem) => FieldKind::OptionalMulti(elem),
+                None => FieldKind::Optional(inner),
+            }
+        } else if let Some(elem) = unwrap_type(&field.ty, "Vec") {
+            FieldKind::Multi(elem)
+        } else {
+            FieldKind::Single
+        };
+
+        Ok(Self {
+            field,
+            key,
+            skip,
+            default,
+            kind,
+        })
+    }
+
+    /// The field name without a raw identifier prefix.
+    fn name(&self) -> String {
+        let name = self.field.ident.as_ref().expect("named field").to_string();
+        name.strip_prefix("r#").map(str::to_owned).unwrap_or(name)
+    }
+
+    fn default_expr(&self) -> TokenStream {
+        let span = self.field.ty.span();
+        match &self.default {
+            DefaultValue::Path(path) => quote_spanned! {span=> #path() },
+            _ => quote_spanned! {span=> ::core::default::Default::default() },
+        }
+    }
+}
+
+fn parse_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<Vec<MapField<'a>>> {
+    let fields = match &input.data {
+        syn::Data::Struct(data) => match &data.fields {
+            syn::Fields::Named(named) => &named.named,
+            fields => {
+                return Err(syn::Error::new_spanned(
+                    fields,
+                    format!("#[derive({derive})] requires a struct with named fields"),
+                ))
+            }
+        },
+        syn::Data::Enum(data) => {
+            return Err(syn::Error::new_spanned(
+                data.enum_token,
+                format!("#[derive({derive})] can't be used on enums"),
+            ))
+        }
+        syn::Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                format!("#[derive({derive})] can't be used on unions"),
+            ))
+        }
+    };
+    fields.iter().map(MapField::parse).collect()
+}
+
+/// Returns the generics for the impl and the lifetime of the map elements.
+///
+/// A struct with one lifetime parameter uses it for the map elements, so `Node<'core>` fields line
+/// up with `Map<'core>`. Structs without lifetimes get a fresh `'elem` parameter.
+fn map_lifetime(input: &DeriveInput, derive: &str) -> syn::Result<(Generics, Lifetime)> {
+    let mut lifetimes = input.generics.lifetimes();
+    match (lifetimes.next(), lifetimes.next()) {
+        (None, _) => {
+            let lifetime = Lifetime::new("'elem", proc_macro2::Span::call_site());
+            let mut generics = input.generics.clone();
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+            );
+            Ok((generics, lifetime))
+        }
+        (Some(param), None) => Ok((input.generics.clone(), param.lifetime.clone())),
+        (Some(_), Some(second)) => Err(syn::Error::new_spanned(
+            second,
+            format!("#[derive({derive})] supports at most one lifetime parameter"),
+        )),
+    }
+}
+
+pub fn impl_into_owned_map(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let fields = parse_fields(input, "IntoOwnedMap")?;
+    let (impl_generics, lifetime) = map_lifetime(input, "IntoOwnedMap")?;
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let stores = fields.iter().filter(|f| !f.skip).map(|f| {
+        let ident = &f.field.ident;
+        let key = &f.key;
+        let span = f.field.ty.span();
+        match f.kind {
+            FieldKind::Single => quote_spanned! {span=>
+                map.set(#key, &self.#ident)?;
+            },
+            FieldKind::Optional(_) => quote_spanned! {span=>
+                if let ::core::option::Option::Some(value) = &self.#ident {
+                    map.set(#key, value)?;
+                }
+            },
+            FieldKind::Multi(_) => quote_spanned! {span=>
+                for value in &self.#ident {
+                    map.append(#key, value)?;
+                }
+            },
+            FieldKind::OptionalMulti(_) => quote_spanned! {span=>
+                if let ::core::option::Option::Some(values) = &self.#ident {
+                    for value in values {
+                        map.append(#key, value)?;
+                    }
+                }
+            },
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics rustsynth::map::IntoOwnedMap<#lifetime> for #name #ty_generics #where_clause {
+            fn into_owned_map(self) -> rustsynth::map::MapResult<rustsynth::map::Map<#lifetime>> {
+                let mut map = rustsynth::map::Map::new()?;
+                #( #stores )*
+                ::core::result::Result::Ok(map)
+            }
+        }
+    })
+}
+
+pub fn impl_from_map(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let fields = parse_fields(input, "FromMap")?;
+    let (impl_generics, lifetime) = map_lifetime(input, "FromMap")?;
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let loads = fields.iter().map(|f| {
+        let ident = &f.field.ident;
+        let key = &f.key;
+        let ty = &f.field.ty;
+        let span = ty.span();
+        if f.skip {
+            let default = f.default_expr();
+            return quote_spanned! {span=> #ident: #default, };
+        }
+
+        let missing = match (&f.default, &f.kind) {
+            (DefaultValue::None, FieldKind::Single) => {
+                let field = format!("{name}::{}", f.name());
+                quote! {
+                    return ::core::result::Result::Err(rustsynth::map::MapError::MissingField {
+                        key: #key,
+                        field: #field,
+                    })
+                }
+            }
+            (DefaultValue::None, FieldKind::Optional(_) | FieldKind::OptionalMulti(_)) => {
+                quote! { ::core::option::Option::None }
+            }
+            (DefaultValue::None, FieldKind::Multi(_)) => quote! { ::std::vec::Vec::new() },
+            _ => f.default_expr(),
+        };
+
+        let load = match f.kind {
+            FieldKind::Single => quote_spanned! {span=>
+                map.get::<#ty>(#key)
+            },
+            FieldKind::Optional(inner) => quote_spanned! {span=>
+                map.get::<#inner>(#key).map(::core::option::Option::Some)
+            },
+            FieldKind::Multi(elem) => quote_spanned! {span=>
+                map.get_iter::<#elem>(#key).map(|iter| iter.collect::<::std::vec::Vec<_>>())
+            },
+            FieldKind::OptionalMulti(elem) => quote_spanned! {span=>
+                map.get_iter::<#elem>(#key)
+                    .map(|iter| ::core::option::Option::Some(iter.collect::<::std::vec::Vec<_>>()))
+            },
+        };
+
+        quote_spanned! {span=>
+            #ident: match #load {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(rustsynth::map::MapError::KeyNotFound) => #missing,
+                ::core::result::Result::Err(error) => return ::core::result::Result::Err(error),
+            },
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics rustsynth::map::FromMap<#lifetime> for #name #ty_generics #where_clause {
+            fn from_map(map: &rustsynth::map::MapRef<#lifetime>) -> rustsynth::map::MapResult<Self> {
+                ::core::result::Result::Ok(Self {
+                    #( #loads )*
+                })
+            }
+        }
+    })
+}
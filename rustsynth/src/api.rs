@@ -253,11 +253,9 @@ impl API {
         self.handle.as_ref().mapNumElements.unwrap()(map, key)
     }
 
-    #[allow(unused)]
-    pub(crate) unsafe fn copy_map(&self, map: *mut ffi::VSMap) -> *mut ffi::VSMap {
-        let mut dest = MaybeUninit::uninit();
-        self.handle.as_ref().copyMap.unwrap()(map, dest.as_mut_ptr());
-        dest.as_mut_ptr()
+    /// Copies all keys of `src` into `dst`, replacing keys that exist in both.
+    pub(crate) unsafe fn copy_map(&self, src: &ffi::VSMap, dst: &mut ffi::VSMap) {
+        self.handle.as_ref().copyMap.unwrap()(src, dst);
     }
 
     pub(crate) unsafe fn map_num_keys(&self, map: &ffi::VSMap) -> c_int {
@@ -887,10 +885,11 @@ impl API {
             msg: *const std::ffi::c_char,
             userdata: *mut c_void,
         ),
+        free: ffi::VSLogHandlerFree,
         userdata: *mut c_void,
         core: *mut ffi::VSCore,
     ) -> *mut ffi::VSLogHandle {
-        unsafe { self.handle.as_ref().addLogHandler.unwrap()(Some(handler), None, userdata, core) }
+        unsafe { self.handle.as_ref().addLogHandler.unwrap()(Some(handler), free, userdata, core) }
     }
 
     pub(crate) fn remove_log_handler(
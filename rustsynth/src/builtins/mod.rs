@@ -0,0 +1,74 @@
+//! Typed wrappers for the functions built into the core.
+//!
+//! The `std` and `resize` namespaces are always available, so their most used functions get typed
+//! methods here instead of going through [`Plugin::invoke()`](crate::plugin::Plugin::invoke) with
+//! a hand-built map. Nodes don't know which core they belong to, so the core is passed in.
+//!
+//! # Example
+//! ```no_run
+//! use rustsynth::{
+//!     builtins::ResizeArgs,
+//!     core::CoreRef,
+//!     format::PresetVideoFormat,
+//!     frame::MatrixCoefficients,
+//!     node::Node,
+//!     plugin::PluginResult,
+//! };
+//!
+//! fn prepare<'core>(core: &CoreRef<'core>, clip: &Node<'core>) -> PluginResult<Node<'core>> {
+//!     let clip = clip.std(core).trim(0, 99)?;
+//!     let clip = clip.std(core).crop(8, 8, 0, 0)?;
+//!     clip.resize(core).bicubic(
+//!         &ResizeArgs::new(1280, 720)
+//!             .format(PresetVideoFormat::YUV420P10)
+//!             .matrix(MatrixCoefficients::BT709),
+//!     )
+//! }
+//! ```
+use crate::{
+    core::CoreRef,
+    map::Map,
+    node::Node,
+    plugin::{PluginError, PluginResult},
+};
+
+mod resize;
+mod standard;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::resize::{Resize, ResizeArgs};
+pub use self::standard::{FrameProp, Std};
+
+impl<'core> Node<'core> {
+    /// Typed access to the `std` functions taking this node.
+    #[inline]
+    #[must_use]
+    pub const fn std<'a>(&'a self, core: &'a CoreRef<'core>) -> Std<'a, 'core> {
+        Std::new(self, core)
+    }
+
+    /// Typed access to the `resize` functions taking this node.
+    #[inline]
+    #[must_use]
+    pub const fn resize<'a>(&'a self, core: &'a CoreRef<'core>) -> Resize<'a, 'core> {
+        Resize::new(self, core)
+    }
+}
+
+/// Invokes `namespace.name` and returns the `clip` it outputs.
+fn invoke<'core>(
+    core: &CoreRef<'core>,
+    namespace: &str,
+    name: &str,
+    args: &Map<'core>,
+) -> PluginResult<Node<'core>> {
+    let plugin = core
+        .plugin_by_namespace(namespace)
+        .ok_or_else(|| PluginError::PluginNotFound(namespace.to_owned()))?;
+    plugin
+        .try_invoke(name, args)?
+        .get_node("clip")
+        .map_err(PluginError::OutputMapError)
+}
@@ -0,0 +1,229 @@
+use crate::{
+    core::CoreRef,
+    format::PresetVideoFormat,
+    frame::{
+        ChromaLocation, ColorPrimaries, ColorRange, MatrixCoefficients, TransferCharacteristics,
+    },
+    map::Map,
+    node::Node,
+    plugin::PluginResult,
+};
+
+use super::invoke;
+
+/// The `resize` functions taking a node, created by [`Node::resize()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Resize<'a, 'core> {
+    node: &'a Node<'core>,
+    core: &'a CoreRef<'core>,
+}
+
+/// Arguments shared by all the `resize` kernels.
+///
+/// Everything left unset keeps the value of the input clip or its frame properties.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResizeArgs {
+    width: Option<i64>,
+    height: Option<i64>,
+    format: Option<PresetVideoFormat>,
+    matrix: Option<MatrixCoefficients>,
+    transfer: Option<TransferCharacteristics>,
+    primaries: Option<ColorPrimaries>,
+    range: Option<ColorRange>,
+    chroma_location: Option<ChromaLocation>,
+    matrix_in: Option<MatrixCoefficients>,
+    transfer_in: Option<TransferCharacteristics>,
+    primaries_in: Option<ColorPrimaries>,
+    range_in: Option<ColorRange>,
+    chroma_location_in: Option<ChromaLocation>,
+    filter_param_a: Option<f64>,
+    filter_param_b: Option<f64>,
+}
+
+/// The `range` arguments of `resize` count the other way round from the `_ColorRange` property.
+const fn range_arg(range: ColorRange) -> i64 {
+    match range {
+        ColorRange::Limited => 0,
+        ColorRange::Full => 1,
+    }
+}
+
+impl ResizeArgs {
+    /// Resizes to `width`x`height`.
+    #[must_use]
+    pub fn new(width: i64, height: i64) -> Self {
+        Self {
+            width: Some(width),
+            height: Some(height),
+            ..Self::default()
+        }
+    }
+
+    /// Converts to `format`.
+    #[must_use]
+    pub const fn format(mut self, format: PresetVideoFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets the output matrix coefficients.
+    #[must_use]
+    pub const fn matrix(mut self, matrix: MatrixCoefficients) -> Self {
+        self.matrix = Some(matrix);
+        self
+    }
+
+    /// Sets the output transfer characteristics.
+    #[must_use]
+    pub const fn transfer(mut self, transfer: TransferCharacteristics) -> Self {
+        self.transfer = Some(transfer);
+        self
+    }
+
+    /// Sets the output color primaries.
+    #[must_use]
+    pub const fn primaries(mut self, primaries: ColorPrimaries) -> Self {
+        self.primaries = Some(primaries);
+        self
+    }
+
+    /// Sets the output color range.
+    #[must_use]
+    pub const fn range(mut self, range: ColorRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets the output chroma location.
+    #[must_use]
+    pub const fn chroma_location(mut self, chroma_location: ChromaLocation) -> Self {
+        self.chroma_location = Some(chroma_location);
+        self
+    }
+
+    /// Overrides the input matrix coefficients.
+    #[must_use]
+    pub const fn matrix_in(mut self, matrix: MatrixCoefficients) -> Self {
+        self.matrix_in = Some(matrix);
+        self
+    }
+
+    /// Overrides the input transfer characteristics.
+    #[must_use]
+    pub const fn transfer_in(mut self, transfer: TransferCharacteristics) -> Self {
+        self.transfer_in = Some(transfer);
+        self
+    }
+
+    /// Overrides the input color primaries.
+    #[must_use]
+    pub const fn primaries_in(mut self, primaries: ColorPrimaries) -> Self {
+        self.primaries_in = Some(primaries);
+        self
+    }
+
+    /// Overrides the input color range.
+    #[must_use]
+    pub const fn range_in(mut self, range: ColorRange) -> Self {
+        self.range_in = Some(range);
+        self
+    }
+
+    /// Overrides the input chroma location.
+    #[must_use]
+    pub const fn chroma_location_in(mut self, chroma_location: ChromaLocation) -> Self {
+        self.chroma_location_in = Some(chroma_location);
+        self
+    }
+
+    /// Sets the kernel parameters, `b` and `c` for bicubic and the number of taps for lanczos.
+    #[must_use]
+    pub const fn filter_params(mut self, a: f64, b: Option<f64>) -> Self {
+        self.filter_param_a = Some(a);
+        self.filter_param_b = b;
+        self
+    }
+
+    fn to_map<'core>(self, node: &Node<'core>) -> PluginResult<Map<'core>> {
+        let mut args = Map::new()?;
+        args.set_node("clip", node)?;
+
+        let ints = [
+            ("width", self.width),
+            ("height", self.height),
+            ("format", self.format.map(|v| v as i64)),
+            ("matrix", self.matrix.map(|v| v as i64)),
+            ("transfer", self.transfer.map(|v| v as i64)),
+            ("primaries", self.primaries.map(|v| v as i64)),
+            ("range", self.range.map(range_arg)),
+            ("chromaloc", self.chroma_location.map(|v| v as i64)),
+            ("matrix_in", self.matrix_in.map(|v| v as i64)),
+            ("transfer_in", self.transfer_in.map(|v| v as i64)),
+            ("primaries_in", self.primaries_in.map(|v| v as i64)),
+            ("range_in", self.range_in.map(range_arg)),
+            ("chromaloc_in", self.chroma_location_in.map(|v| v as i64)),
+        ];
+        for (key, value) in ints {
+            if let Some(value) = value {
+                args.set_int(key, value)?;
+            }
+        }
+
+        let floats = [
+            ("filter_param_a", self.filter_param_a),
+            ("filter_param_b", self.filter_param_b),
+        ];
+        for (key, value) in floats {
+            if let Some(value) = value {
+                args.set_float(key, value)?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl<'a, 'core> Resize<'a, 'core> {
+    #[inline]
+    pub(crate) const fn new(node: &'a Node<'core>, core: &'a CoreRef<'core>) -> Self {
+        Self { node, core }
+    }
+
+    fn invoke(&self, name: &str, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        invoke(self.core, "resize", name, &args.to_map(self.node)?)
+    }
+
+    /// `resize.Point`
+    pub fn point(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Point", args)
+    }
+
+    /// `resize.Bilinear`
+    pub fn bilinear(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Bilinear", args)
+    }
+
+    /// `resize.Bicubic`, set `b` and `c` with [`ResizeArgs::filter_params()`].
+    pub fn bicubic(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Bicubic", args)
+    }
+
+    /// `resize.Lanczos`, set the number of taps with [`ResizeArgs::filter_params()`].
+    pub fn lanczos(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Lanczos", args)
+    }
+
+    /// `resize.Spline16`
+    pub fn spline16(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Spline16", args)
+    }
+
+    /// `resize.Spline36`
+    pub fn spline36(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Spline36", args)
+    }
+
+    /// `resize.Spline64`
+    pub fn spline64(&self, args: &ResizeArgs) -> PluginResult<Node<'core>> {
+        self.invoke("Spline64", args)
+    }
+}
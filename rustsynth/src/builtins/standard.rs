@@ -0,0 +1,197 @@
+use crate::{
+    core::CoreRef,
+    format::{ColorFamily, PresetVideoFormat},
+    map::Map,
+    node::Node,
+    plugin::PluginResult,
+};
+
+use super::invoke;
+
+/// The `std` functions taking a node, created by [`Node::std()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Std<'a, 'core> {
+    node: &'a Node<'core>,
+    core: &'a CoreRef<'core>,
+}
+
+/// A value for [`Std::set_frame_prop()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameProp {
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+    Data(Vec<String>),
+}
+
+impl From<i64> for FrameProp {
+    fn from(value: i64) -> Self {
+        Self::Int(vec![value])
+    }
+}
+
+impl From<f64> for FrameProp {
+    fn from(value: f64) -> Self {
+        Self::Float(vec![value])
+    }
+}
+
+impl From<&str> for FrameProp {
+    fn from(value: &str) -> Self {
+        Self::Data(vec![value.to_owned()])
+    }
+}
+
+impl From<String> for FrameProp {
+    fn from(value: String) -> Self {
+        Self::Data(vec![value])
+    }
+}
+
+impl From<Vec<i64>> for FrameProp {
+    fn from(value: Vec<i64>) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<Vec<f64>> for FrameProp {
+    fn from(value: Vec<f64>) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<Vec<String>> for FrameProp {
+    fn from(value: Vec<String>) -> Self {
+        Self::Data(value)
+    }
+}
+
+impl<'a, 'core> Std<'a, 'core> {
+    #[inline]
+    pub(crate) const fn new(node: &'a Node<'core>, core: &'a CoreRef<'core>) -> Self {
+        Self { node, core }
+    }
+
+    fn clip_args(&self) -> PluginResult<Map<'core>> {
+        let mut args = Map::new()?;
+        args.set_node("clip", self.node)?;
+        Ok(args)
+    }
+
+    fn invoke(&self, name: &str, args: &Map<'core>) -> PluginResult<Node<'core>> {
+        invoke(self.core, "std", name, args)
+    }
+
+    /// `std.Trim`: keeps the frames from `first` to `last`, inclusive.
+    pub fn trim(&self, first: i64, last: i64) -> PluginResult<Node<'core>> {
+        let mut args = self.clip_args()?;
+        args.set_int("first", first)?;
+        args.set_int("last", last)?;
+        self.invoke("Trim", &args)
+    }
+
+    /// `std.Splice`: appends `others` to this node.
+    ///
+    /// All clips must have the same format and dimensions unless `mismatch` is set.
+    pub fn splice(&self, others: &[Node<'core>], mismatch: bool) -> PluginResult<Node<'core>> {
+        let mut args = Map::new()?;
+        args.append_node("clips", self.node)?;
+        for other in others {
+            args.append_node("clips", other)?;
+        }
+        args.set_int("mismatch", i64::from(mismatch))?;
+        self.invoke("Splice", &args)
+    }
+
+    /// `std.Crop`: removes the given number of pixels from each side.
+    pub fn crop(&self, left: i64, right: i64, top: i64, bottom: i64) -> PluginResult<Node<'core>> {
+        let mut args = self.clip_args()?;
+        args.set_int("left", left)?;
+        args.set_int("right", right)?;
+        args.set_int("top", top)?;
+        args.set_int("bottom", bottom)?;
+        self.invoke("Crop", &args)
+    }
+
+    /// `std.AssumeFPS`: changes the frame rate without adding or dropping frames.
+    pub fn assume_fps(&self, fps_num: i64, fps_den: i64) -> PluginResult<Node<'core>> {
+        let mut args = self.clip_args()?;
+        args.set_int("fpsnum", fps_num)?;
+        args.set_int("fpsden", fps_den)?;
+        self.invoke("AssumeFPS", &args)
+    }
+
+    /// `std.ShufflePlanes`: builds a clip of `color_family` from `planes` of this node.
+    ///
+    /// Pass a single plane to extract it as a gray clip.
+    pub fn shuffle_planes(
+        &self,
+        planes: &[i64],
+        color_family: ColorFamily,
+    ) -> PluginResult<Node<'core>> {
+        let mut args = Map::new()?;
+        args.append_node("clips", self.node)?;
+        args.set_int_array("planes", planes.to_vec())?;
+        args.set_int("colorfamily", color_family as i64)?;
+        self.invoke("ShufflePlanes", &args)
+    }
+
+    /// `std.Expr`: evaluates one RPN expression per plane, this node is `x`.
+    ///
+    /// The last expression is repeated for the remaining planes. `format` changes the output
+    /// format, keeping the input format if `None`.
+    pub fn expr(
+        &self,
+        exprs: &[&str],
+        format: Option<PresetVideoFormat>,
+    ) -> PluginResult<Node<'core>> {
+        let mut args = Map::new()?;
+        args.append_node("clips", self.node)?;
+        for expr in exprs {
+            args.append_string("expr", expr)?;
+        }
+        if let Some(format) = format {
+            args.set_int("format", format as i64)?;
+        }
+        self.invoke("Expr", &args)
+    }
+
+    /// `std.BlankClip`: creates a clip with the same properties as this node, filled with `color`.
+    ///
+    /// An empty `color` gives black.
+    pub fn blank_clip(&self, color: &[f64]) -> PluginResult<Node<'core>> {
+        let mut args = self.clip_args()?;
+        if !color.is_empty() {
+            args.set_float_array("color", color.to_vec())?;
+        }
+        self.invoke("BlankClip", &args)
+    }
+
+    /// `std.SetFrameProp`: sets `prop` on every frame.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rustsynth::{core::CoreRef, node::Node, plugin::PluginResult};
+    /// # fn f<'core>(core: &CoreRef<'core>, clip: &Node<'core>) -> PluginResult<Node<'core>> {
+    /// let clip = clip.std(core).set_frame_prop("_Matrix", 1)?;
+    /// clip.std(core).set_frame_prop("Source", "camera_a")
+    /// # }
+    /// ```
+    pub fn set_frame_prop(
+        &self,
+        prop: &str,
+        value: impl Into<FrameProp>,
+    ) -> PluginResult<Node<'core>> {
+        let mut args = self.clip_args()?;
+        args.set_string("prop", prop)?;
+        match value.into() {
+            FrameProp::Int(values) => args.set_int_array("intval", values)?,
+            FrameProp::Float(values) => args.set_float_array("floatval", values)?,
+            FrameProp::Data(values) => {
+                for value in &values {
+                    args.append_string("data", value)?;
+                }
+            }
+        }
+        self.invoke("SetFrameProp", &args)
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use crate::api::API;
+    use crate::builtins::ResizeArgs;
+    use crate::core::{CoreCreationFlags, CoreRef};
+    use crate::format::PresetVideoFormat;
+    use crate::map::Map;
+    use crate::node::Node;
+
+    fn blank_clip<'core>(core: &CoreRef<'core>) -> Node<'core> {
+        let mut args = Map::new().unwrap();
+        args.set_int("width", 640).unwrap();
+        args.set_int("height", 480).unwrap();
+        args.set_int("length", 100).unwrap();
+        args.set_int("format", PresetVideoFormat::YUV420P8 as i64)
+            .unwrap();
+        core.plugin_by_namespace("std")
+            .unwrap()
+            .invoke("BlankClip", &args)
+            .unwrap()
+            .get_node("clip")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_std_functions() {
+        let _api = API::get().expect("Failed to get VapourSynth API");
+        let core = CoreRef::new(CoreCreationFlags::NONE);
+        let clip = blank_clip(&core);
+
+        let trimmed = clip.std(&core).trim(10, 19).unwrap();
+        assert_eq!(trimmed.video_info().unwrap().num_frames, 10);
+
+        let spliced = trimmed
+            .std(&core)
+            .splice(std::slice::from_ref(&trimmed), false)
+            .unwrap();
+        assert_eq!(spliced.video_info().unwrap().num_frames, 20);
+
+        let cropped = clip.std(&core).crop(8, 8, 4, 4).unwrap();
+        let vi = cropped.video_info().unwrap();
+        assert_eq!((vi.width, vi.height), (624, 472));
+
+        let retimed = clip.std(&core).assume_fps(24000, 1001).unwrap();
+        let vi = retimed.video_info().unwrap();
+        assert_eq!((vi.fps_num, vi.fps_den), (24000, 1001));
+
+        let tagged = clip.std(&core).set_frame_prop("Answer", 42).unwrap();
+        let frame = tagged.get_frame(0).unwrap();
+        assert_eq!(frame.properties().get_int("Answer").unwrap(), 42);
+
+        assert!(clip.std(&core).trim(-5, 3).is_err());
+    }
+
+    #[test]
+    fn test_resize_functions() {
+        let _api = API::get().expect("Failed to get VapourSynth API");
+        let core = CoreRef::new(CoreCreationFlags::NONE);
+        let clip = blank_clip(&core);
+
+        let resized = clip
+            .resize(&core)
+            .bicubic(&ResizeArgs::new(320, 240).format(PresetVideoFormat::YUV444P16))
+            .unwrap();
+        let vi = resized.video_info().unwrap();
+        assert_eq!((vi.width, vi.height), (320, 240));
+        assert_eq!(vi.format.bits_per_sample, 16);
+    }
+}
@@ -0,0 +1,338 @@
+// Generated by rustsynth::codegen::BindingGenerator. Do not edit by hand.
+
+/// `std` (com.vapoursynth.std): VapourSynth Core Functions
+#[allow(clippy::all, dead_code)]
+pub mod std {
+    /// Typed access to the `std` namespace.
+    #[derive(Debug, Clone, Copy)]
+    pub struct StdPlugin<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+    }
+
+    impl<'core> StdPlugin<'core> {
+        /// Looks up the `std` namespace, returning `None` if it isn't loaded.
+        pub fn new(core: &::rustsynth::core::CoreRef<'core>) -> ::core::option::Option<Self> {
+            core.plugin_by_namespace("std").map(|plugin| Self { plugin })
+        }
+
+        /// `std.Crop(clip:vnode;left:int:opt;right:int:opt;)`
+        pub fn crop(&self, clip: ::rustsynth::node::Node<'core>) -> Crop<'core> {
+            Crop {
+                plugin: self.plugin,
+                clip,
+                left: ::core::option::Option::None,
+                right: ::core::option::Option::None,
+            }
+        }
+
+        /// `std.Loop(clip:vnode;times:int:opt;)`
+        pub fn r#loop(&self, clip: ::rustsynth::node::Node<'core>) -> Loop<'core> {
+            Loop {
+                plugin: self.plugin,
+                clip,
+                times: ::core::option::Option::None,
+            }
+        }
+
+        /// `std.SetFrameProps(clip:vnode;any;)`
+        pub fn set_frame_props(&self, clip: ::rustsynth::node::Node<'core>) -> SetFrameProps<'core> {
+            SetFrameProps {
+                plugin: self.plugin,
+                clip,
+            }
+        }
+
+        /// `std.Expr(clips:vnode[];expr:data[];format:int:opt;)`
+        pub fn expr(&self, clips: ::std::vec::Vec<::rustsynth::node::Node<'core>>, expr: ::std::vec::Vec<::std::string::String>) -> Expr<'core> {
+            Expr {
+                plugin: self.plugin,
+                clips,
+                expr,
+                format: ::core::option::Option::None,
+            }
+        }
+
+        /// `std.PropToClip(clip:vnode;prop:data:opt;)`
+        pub fn prop_to_clip(&self, clip: ::rustsynth::node::Node<'core>) -> PropToClip<'core> {
+            PropToClip {
+                plugin: self.plugin,
+                clip,
+                prop: ::core::option::Option::None,
+            }
+        }
+
+        /// `std.Weird(self:int;plugin:float[]:opt:empty;call:func:opt;type:vframe;)`
+        pub fn weird(&self, self_: i64, r#type: ::rustsynth::frame::Frame<'core>) -> Weird<'core> {
+            Weird {
+                plugin: self.plugin,
+                self_,
+                plugin_: ::core::option::Option::None,
+                call_: ::core::option::Option::None,
+                r#type,
+            }
+        }
+
+        /// `std.New(a:anode[]:opt;)`
+        pub fn new_(&self) -> New<'core> {
+            New {
+                plugin: self.plugin,
+                a: ::core::option::Option::None,
+            }
+        }
+
+        /// `std.AudioGain(clip:anode;gain:float[]:opt;)`
+        pub fn audio_gain(&self, clip: ::rustsynth::node::Node<'core>) -> AudioGain<'core> {
+            AudioGain {
+                plugin: self.plugin,
+                clip,
+                gain: ::core::option::Option::None,
+            }
+        }
+    }
+
+    /// Arguments of `std.Crop`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct Crop<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        clip: ::rustsynth::node::Node<'core>,
+        left: ::core::option::Option<i64>,
+        right: ::core::option::Option<i64>,
+    }
+
+    impl<'core> Crop<'core> {
+        /// Sets `left:int:opt`.
+        pub fn left(mut self, left: i64) -> Self {
+            self.left = ::core::option::Option::Some(left);
+            self
+        }
+
+        /// Sets `right:int:opt`.
+        pub fn right(mut self, right: i64) -> Self {
+            self.right = ::core::option::Option::Some(right);
+            self
+        }
+
+        /// Invokes `std.Crop`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            args.set("clip", &self.clip)?;
+            if let ::core::option::Option::Some(value) = &self.left {
+                args.set("left", value)?;
+            }
+            if let ::core::option::Option::Some(value) = &self.right {
+                args.set("right", value)?;
+            }
+            let out = self.plugin.try_invoke("Crop", &args)?;
+            out.get("clip").map_err(::rustsynth::plugin::PluginError::OutputMapError)
+        }
+    }
+
+    /// Arguments of `std.Loop`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct Loop<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        clip: ::rustsynth::node::Node<'core>,
+        times: ::core::option::Option<i64>,
+    }
+
+    impl<'core> Loop<'core> {
+        /// Sets `times:int:opt`.
+        pub fn times(mut self, times: i64) -> Self {
+            self.times = ::core::option::Option::Some(times);
+            self
+        }
+
+        /// Invokes `std.Loop`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            args.set("clip", &self.clip)?;
+            if let ::core::option::Option::Some(value) = &self.times {
+                args.set("times", value)?;
+            }
+            let out = self.plugin.try_invoke("Loop", &args)?;
+            out.get("clip").map_err(::rustsynth::plugin::PluginError::OutputMapError)
+        }
+    }
+
+    /// Arguments of `std.SetFrameProps`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct SetFrameProps<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        clip: ::rustsynth::node::Node<'core>,
+    }
+
+    impl<'core> SetFrameProps<'core> {
+        /// Invokes `std.SetFrameProps`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            args.set("clip", &self.clip)?;
+            let out = self.plugin.try_invoke("SetFrameProps", &args)?;
+            out.get("clip").map_err(::rustsynth::plugin::PluginError::OutputMapError)
+        }
+    }
+
+    /// Arguments of `std.Expr`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct Expr<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        clips: ::std::vec::Vec<::rustsynth::node::Node<'core>>,
+        expr: ::std::vec::Vec<::std::string::String>,
+        format: ::core::option::Option<i64>,
+    }
+
+    impl<'core> Expr<'core> {
+        /// Sets `format:int:opt`.
+        pub fn format(mut self, format: i64) -> Self {
+            self.format = ::core::option::Option::Some(format);
+            self
+        }
+
+        /// Invokes `std.Expr`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            for value in &self.clips {
+                args.append("clips", value)?;
+            }
+            for value in &self.expr {
+                args.append("expr", value)?;
+            }
+            if let ::core::option::Option::Some(value) = &self.format {
+                args.set("format", value)?;
+            }
+            let out = self.plugin.try_invoke("Expr", &args)?;
+            out.get("clip").map_err(::rustsynth::plugin::PluginError::OutputMapError)
+        }
+    }
+
+    /// Arguments of `std.PropToClip`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct PropToClip<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        clip: ::rustsynth::node::Node<'core>,
+        prop: ::core::option::Option<::std::string::String>,
+    }
+
+    impl<'core> PropToClip<'core> {
+        /// Sets `prop:data:opt`.
+        pub fn prop(mut self, prop: &str) -> Self {
+            self.prop = ::core::option::Option::Some(prop.to_owned());
+            self
+        }
+
+        /// Invokes `std.PropToClip`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            args.set("clip", &self.clip)?;
+            if let ::core::option::Option::Some(value) = &self.prop {
+                args.set("prop", value)?;
+            }
+            let out = self.plugin.try_invoke("PropToClip", &args)?;
+            out.get("clip").map_err(::rustsynth::plugin::PluginError::OutputMapError)
+        }
+    }
+
+    /// Arguments of `std.Weird`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct Weird<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        self_: i64,
+        plugin_: ::core::option::Option<::std::vec::Vec<f64>>,
+        call_: ::core::option::Option<::rustsynth::function::Function<'core>>,
+        r#type: ::rustsynth::frame::Frame<'core>,
+    }
+
+    impl<'core> Weird<'core> {
+        /// Sets `plugin:float[]:opt:empty`.
+        pub fn plugin_(mut self, plugin_: ::std::vec::Vec<f64>) -> Self {
+            self.plugin_ = ::core::option::Option::Some(plugin_);
+            self
+        }
+
+        /// Sets `call:func:opt`.
+        pub fn call_(mut self, call_: ::rustsynth::function::Function<'core>) -> Self {
+            self.call_ = ::core::option::Option::Some(call_);
+            self
+        }
+
+        /// Invokes `std.Weird`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::map::Map<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            args.set("self", &self.self_)?;
+            if let ::core::option::Option::Some(value) = &self.plugin_ {
+                for value in value {
+                    args.append("plugin", value)?;
+                }
+            }
+            if let ::core::option::Option::Some(value) = &self.call_ {
+                args.set("call", value)?;
+            }
+            args.set("type", &self.r#type)?;
+            let out = self.plugin.try_invoke("Weird", &args)?;
+            ::core::result::Result::Ok(out)
+        }
+    }
+
+    /// Arguments of `std.New`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct New<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        a: ::core::option::Option<::std::vec::Vec<::rustsynth::node::Node<'core>>>,
+    }
+
+    impl<'core> New<'core> {
+        /// Sets `a:anode[]:opt`.
+        pub fn a(mut self, a: ::std::vec::Vec<::rustsynth::node::Node<'core>>) -> Self {
+            self.a = ::core::option::Option::Some(a);
+            self
+        }
+
+        /// Invokes `std.New`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::map::Map<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            if let ::core::option::Option::Some(value) = &self.a {
+                for value in value {
+                    args.append("a", value)?;
+                }
+            }
+            let out = self.plugin.try_invoke("New", &args)?;
+            ::core::result::Result::Ok(out)
+        }
+    }
+
+    /// Arguments of `std.AudioGain`.
+    #[derive(Clone)]
+    #[must_use]
+    pub struct AudioGain<'core> {
+        plugin: ::rustsynth::plugin::Plugin<'core>,
+        clip: ::rustsynth::node::Node<'core>,
+        gain: ::core::option::Option<::std::vec::Vec<f64>>,
+    }
+
+    impl<'core> AudioGain<'core> {
+        /// Sets `gain:float[]:opt`.
+        pub fn gain(mut self, gain: ::std::vec::Vec<f64>) -> Self {
+            self.gain = ::core::option::Option::Some(gain);
+            self
+        }
+
+        /// Invokes `std.AudioGain`.
+        pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {
+            let mut args = ::rustsynth::map::Map::new()?;
+            args.set("clip", &self.clip)?;
+            if let ::core::option::Option::Some(value) = &self.gain {
+                for value in value {
+                    args.append("gain", value)?;
+                }
+            }
+            let out = self.plugin.try_invoke("AudioGain", &args)?;
+            out.get("clip").map_err(::rustsynth::plugin::PluginError::OutputMapError)
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Generates typed Rust bindings for the functions of loaded plugins.
+//!
+//! Every plugin namespace becomes a module with a handle struct (`std` gets `StdPlugin`) and one
+//! builder per function. Required arguments are taken by the handle method, optional ones are set
+//! on the builder, and `call()` invokes the function:
+//!
+//! ```ignore
+//! let cropped = StdPlugin::new(&core).unwrap().crop(clip).left(8).right(8).call()?;
+//! ```
+//!
+//! Functions returning a single value return it directly, everything else returns the output
+//! [`Map`](crate::map::Map).
+//!
+//! # Example
+//! ```no_run
+//! use rustsynth::{codegen::BindingGenerator, core::{CoreCreationFlags, CoreRef}};
+//!
+//! let core = CoreRef::new(CoreCreationFlags::NONE);
+//! let source = BindingGenerator::new()
+//!     .namespace("std")
+//!     .namespace("resize")
+//!     .generate(&core);
+//! std::fs::write("src/vs.rs", source).unwrap();
+//! ```
+use std::fmt::Write;
+
+use crate::{
+    core::CoreRef,
+    plugin::Plugin,
+    signature::{ArgType, Argument, Signature},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// A plugin as seen by the generator.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PluginSpec {
+    pub namespace: String,
+    pub id: String,
+    pub name: String,
+    pub functions: Vec<FunctionSpec>,
+}
+
+impl PluginSpec {
+    /// Collects the functions of `plugin`.
+    ///
+    /// Returns `None` if the plugin has no namespace. Functions with unparsable signatures are
+    /// left out.
+    #[must_use]
+    pub fn from_plugin(plugin: &Plugin<'_>) -> Option<Self> {
+        let mut functions: Vec<_> = plugin
+            .functions()
+            .filter_map(|function| {
+                Some(FunctionSpec {
+                    name: function.get_name()?,
+                    arguments: function.signature()?,
+                    returns: function.return_signature()?,
+                })
+            })
+            .collect();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Some(Self {
+            namespace: plugin.namespace()?,
+            id: plugin.id().unwrap_or_default(),
+            name: plugin.name().unwrap_or_default(),
+            functions,
+        })
+    }
+}
+
+/// A plugin function as seen by the generator.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FunctionSpec {
+    pub name: String,
+    pub arguments: Signature,
+    pub returns: Signature,
+}
+
+/// Generates Rust source code with typed bindings for plugin functions.
+#[derive(Debug, Clone, Default)]
+pub struct BindingGenerator {
+    namespaces: Vec<String>,
+}
+
+impl BindingGenerator {
+    /// Creates a generator for every plugin namespace.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the generated bindings to `namespace`. Can be called several times.
+    #[must_use]
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespaces.push(namespace.to_owned());
+        self
+    }
+
+    /// Generates bindings for the plugins loaded in `core`.
+    #[must_use]
+    pub fn generate(&self, core: &CoreRef<'_>) -> String {
+        let mut plugins: Vec<_> = core
+            .plugins()
+            .filter_map(|plugin| PluginSpec::from_plugin(&plugin))
+            .collect();
+        plugins.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+        self.generate_from_specs(&plugins)
+    }
+
+    /// Generates bindings for the given plugins.
+    #[must_use]
+    pub fn generate_from_specs(&self, plugins: &[PluginSpec]) -> String {
+        let mut out = String::from(
+            "// Generated by rustsynth::codegen::BindingGenerator. Do not edit by hand.\n",
+        );
+        for plugin in plugins {
+            if self.namespaces.is_empty() || self.namespaces.contains(&plugin.namespace) {
+                write_plugin(&mut out, plugin);
+            }
+        }
+        out
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Turns `name` into an identifier that doesn't clash with keywords or with `reserved`.
+fn ident(name: &str, reserved: &[&str]) -> String {
+    if matches!(name, "self" | "Self" | "super" | "crate" | "_") || reserved.contains(&name) {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_owned()
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+const fn rust_type(ty: ArgType) -> &'static str {
+    match ty {
+        ArgType::Int => "i64",
+        ArgType::Float => "f64",
+        ArgType::Data => "::std::string::String",
+        ArgType::AudioNode | ArgType::VideoNode => "::rustsynth::node::Node<'core>",
+        ArgType::AudioFrame | ArgType::VideoFrame => "::rustsynth::frame::Frame<'core>",
+        ArgType::Function => "::rustsynth::function::Function<'core>",
+    }
+}
+
+/// The type of the builder field holding `arg`.
+fn field_type(arg: &Argument) -> String {
+    let ty = rust_type(arg.arg_type);
+    match (arg.array, arg.optional) {
+        (false, false) => ty.to_owned(),
+        (false, true) => format!("::core::option::Option<{ty}>"),
+        (true, false) => format!("::std::vec::Vec<{ty}>"),
+        (true, true) => format!("::core::option::Option<::std::vec::Vec<{ty}>>"),
+    }
+}
+
+/// The parameter type taking `arg` and the expression turning the parameter into the field value.
+fn param(arg: &Argument, ident: &str) -> (String, String) {
+    let ty = rust_type(arg.arg_type);
+    if arg.array {
+        (format!("::std::vec::Vec<{ty}>"), ident.to_owned())
+    } else if arg.arg_type == ArgType::Data {
+        ("&str".to_owned(), format!("{ident}.to_owned()"))
+    } else {
+        (ty.to_owned(), ident.to_owned())
+    }
+}
+
+/// Fields and setters of the builders, which must not clash with argument names.
+const BUILDER_RESERVED: &[&str] = &["plugin", "call"];
+/// Methods of the handle struct, which must not clash with function names.
+const HANDLE_RESERVED: &[&str] = &["new", "plugin"];
+
+fn write_plugin(out: &mut String, plugin: &PluginSpec) {
+    let module = ident(&plugin.namespace, &[]);
+    let handle = format!("{}Plugin", camel_case(&plugin.namespace));
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "/// `{}` ({}): {}",
+        plugin.namespace, plugin.id, plugin.name
+    );
+    let _ = writeln!(out, "#[allow(clippy::all, dead_code)]");
+    let _ = writeln!(out, "pub mod {module} {{");
+    let _ = writeln!(
+        out,
+        "    /// Typed access to the `{}` namespace.",
+        plugin.namespace
+    );
+    let _ = writeln!(out, "    #[derive(Debug, Clone, Copy)]");
+    let _ = writeln!(out, "    pub struct {handle}<'core> {{");
+    let _ = writeln!(out, "        plugin: ::rustsynth::plugin::Plugin<'core>,");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "    impl<'core> {handle}<'core> {{");
+    let _ = writeln!(
+        out,
+        "        /// Looks up the `{}` namespace, returning `None` if it isn't loaded.",
+        plugin.namespace
+    );
+    let _ = writeln!(
+        out,
+        "        pub fn new(core: &::rustsynth::core::CoreRef<'core>) -> ::core::option::Option<Self> {{"
+    );
+    let _ = writeln!(
+        out,
+        "            core.plugin_by_namespace({:?}).map(|plugin| Self {{ plugin }})",
+        plugin.namespace
+    );
+    let _ = writeln!(out, "        }}");
+    for function in &plugin.functions {
+        write_entry(out, plugin, function);
+    }
+    let _ = writeln!(out, "    }}");
+    for function in &plugin.functions {
+        write_builder(out, plugin, function);
+    }
+    let _ = writeln!(out, "}}");
+}
+
+fn write_entry(out: &mut String, plugin: &PluginSpec, function: &FunctionSpec) {
+    let method = ident(&snake_case(&function.name), HANDLE_RESERVED);
+    let builder = ident(&function.name, &[]);
+    let required: Vec<_> = function
+        .arguments
+        .arguments
+        .iter()
+        .filter(|arg| !arg.optional)
+        .collect();
+
+    let params: String = required
+        .iter()
+        .map(|arg| {
+            let name = ident(&arg.name, BUILDER_RESERVED);
+            let (ty, _) = param(arg, &name);
+            format!(", {name}: {ty}")
+        })
+        .collect();
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "        /// `{}.{}({})`",
+        plugin.namespace, function.name, function.arguments
+    );
+    let _ = writeln!(
+        out,
+        "        pub fn {method}(&self{params}) -> {builder}<'core> {{"
+    );
+    let _ = writeln!(out, "            {builder} {{");
+    let _ = writeln!(out, "                plugin: self.plugin,");
+    for arg in &function.arguments.arguments {
+        let name = ident(&arg.name, BUILDER_RESERVED);
+        if arg.optional {
+            let _ = writeln!(out, "                {name}: ::core::option::Option::None,");
+        } else {
+            let (_, value) = param(arg, &name);
+            if value == name {
+                let _ = writeln!(out, "                {name},");
+            } else {
+                let _ = writeln!(out, "                {name}: {value},");
+            }
+        }
+    }
+    let _ = writeln!(out, "            }}");
+    let _ = writeln!(out, "        }}");
+}
+
+fn write_builder(out: &mut String, plugin: &PluginSpec, function: &FunctionSpec) {
+    let builder = ident(&function.name, &[]);
+    let args = &function.arguments.arguments;
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "    /// Arguments of `{}.{}`.",
+        plugin.namespace, function.name
+    );
+    let _ = writeln!(out, "    #[derive(Clone)]");
+    let _ = writeln!(out, "    #[must_use]");
+    let _ = writeln!(out, "    pub struct {builder}<'core> {{");
+    let _ = writeln!(out, "        plugin: ::rustsynth::plugin::Plugin<'core>,");
+    for arg in args {
+        let name = ident(&arg.name, BUILDER_RESERVED);
+        let _ = writeln!(out, "        {name}: {},", field_type(arg));
+    }
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "    impl<'core> {builder}<'core> {{");
+
+    for arg in args.iter().filter(|arg| arg.optional) {
+        let name = ident(&arg.name, BUILDER_RESERVED);
+        let (ty, value) = param(arg, &name);
+        let _ = writeln!(out, "        /// Sets `{arg}`.");
+        let _ = writeln!(
+            out,
+            "        pub fn {name}(mut self, {name}: {ty}) -> Self {{"
+        );
+        let _ = writeln!(
+            out,
+            "            self.{name} = ::core::option::Option::Some({value});"
+        );
+        let _ = writeln!(out, "            self");
+        let _ = writeln!(out, "        }}");
+        let _ = writeln!(out);
+    }
+
+    let (return_type, extract) = return_value(&function.returns);
+    let _ = writeln!(
+        out,
+        "        /// Invokes `{}.{}`.",
+        plugin.namespace, function.name
+    );
+    let _ = writeln!(
+        out,
+        "        pub fn call(self) -> ::rustsynth::plugin::PluginResult<{return_type}> {{"
+    );
+    let _ = writeln!(
+        out,
+        "            let mut args = ::rustsynth::map::Map::new()?;"
+    );
+    for arg in args {
+        let name = ident(&arg.name, BUILDER_RESERVED);
+        let key = &arg.name;
+        let (binding, indent) = if arg.optional {
+            let _ = writeln!(
+                out,
+                "            if let ::core::option::Option::Some(value) = &self.{name} {{"
+            );
+            ("value".to_owned(), "                ")
+        } else {
+            (format!("&self.{name}"), "            ")
+        };
+        if arg.array {
+            let _ = writeln!(out, "{indent}for value in {binding} {{");
+            let _ = writeln!(out, "{indent}    args.append({key:?}, value)?;");
+            let _ = writeln!(out, "{indent}}}");
+        } else {
+            let _ = writeln!(out, "{indent}args.set({key:?}, {binding})?;");
+        }
+        if arg.optional {
+            let _ = writeln!(out, "            }}");
+        }
+    }
+    let _ = writeln!(
+        out,
+        "            let out = self.plugin.try_invoke({:?}, &args)?;",
+        function.name
+    );
+    let _ = writeln!(out, "            {extract}");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }}");
+}
+
+/// The return type of `call()` and the expression producing it from the output map `out`.
+fn return_value(returns: &Signature) -> (String, String) {
+    match returns.arguments.as_slice() {
+        [value] if !returns.any && !value.array => (
+            rust_type(value.arg_type).to_owned(),
+            format!(
+                "out.get({:?}).map_err(::rustsynth::plugin::PluginError::OutputMapError)",
+                value.name
+            ),
+        ),
+        _ => (
+            "::rustsynth::map::Map<'core>".to_owned(),
+            "::core::result::Result::Ok(out)".to_owned(),
+        ),
+    }
+}
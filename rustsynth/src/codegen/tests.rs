@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{
+        camel_case, ident, snake_case, BindingGenerator, FunctionSpec, PluginSpec,
+    };
+
+    fn std_spec() -> PluginSpec {
+        PluginSpec {
+            namespace: "std".to_owned(),
+            id: "com.vapoursynth.std".to_owned(),
+            name: "VapourSynth Core Functions".to_owned(),
+            functions: vec![
+                FunctionSpec {
+                    name: "Crop".to_owned(),
+                    arguments: "clip:vnode;left:int:opt;right:int:opt;".parse().unwrap(),
+                    returns: "clip:vnode;".parse().unwrap(),
+                },
+                FunctionSpec {
+                    name: "Loop".to_owned(),
+                    arguments: "clip:vnode;times:int:opt;".parse().unwrap(),
+                    returns: "clip:vnode;".parse().unwrap(),
+                },
+                FunctionSpec {
+                    name: "SetFrameProps".to_owned(),
+                    arguments: "clip:vnode;any;".parse().unwrap(),
+                    returns: "clip:vnode;".parse().unwrap(),
+                },
+                FunctionSpec {
+                    name: "Expr".to_owned(),
+                    arguments: "clips:vnode[];expr:data[];format:int:opt;".parse().unwrap(),
+                    returns: "clip:vnode;".parse().unwrap(),
+                },
+            ],
+        }
+    }
+
+    fn function(name: &str, arguments: &str, returns: &str) -> FunctionSpec {
+        FunctionSpec {
+            name: name.to_owned(),
+            arguments: arguments.parse().unwrap(),
+            returns: returns.parse().unwrap(),
+        }
+    }
+
+    /// `std_spec()` with functions covering the remaining argument types, reserved names and
+    /// return shapes.
+    fn golden_spec() -> PluginSpec {
+        let mut spec = std_spec();
+        spec.functions.extend([
+            function("PropToClip", "clip:vnode;prop:data:opt;", "clip:vnode;"),
+            function(
+                "Weird",
+                "self:int;plugin:float[]:opt:empty;call:func:opt;type:vframe;",
+                "any",
+            ),
+            function("New", "a:anode[]:opt;", "a:int;b:int;"),
+            function("AudioGain", "clip:anode;gain:float[]:opt;", "clip:anode;"),
+        ]);
+        spec
+    }
+
+    /// The bindings generated from `golden_spec()`, compiled as part of the tests so that the
+    /// generated code is type-checked.
+    mod golden {
+        include!("golden.rs");
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(snake_case("Crop"), "crop");
+        assert_eq!(snake_case("ShufflePlanes"), "shuffle_planes");
+        assert_eq!(snake_case("ConvertToRGB"), "convert_to_rgb");
+        assert_eq!(snake_case("RGBToYUV"), "rgb_to_yuv");
+        assert_eq!(snake_case("AddBorders"), "add_borders");
+        assert_eq!(camel_case("std"), "Std");
+        assert_eq!(camel_case("my_plugin"), "MyPlugin");
+        assert_eq!(ident("loop", &[]), "r#loop");
+        assert_eq!(ident("self", &[]), "self_");
+        assert_eq!(ident("plugin", &["plugin"]), "plugin_");
+        assert_eq!(ident("clip", &["plugin"]), "clip");
+    }
+
+    #[test]
+    fn test_generate() {
+        let source = BindingGenerator::new().generate_from_specs(&[std_spec()]);
+
+        assert!(source.contains("pub mod std {"));
+        assert!(source.contains("pub struct StdPlugin<'core> {"));
+        assert!(source
+            .contains("pub fn crop(&self, clip: ::rustsynth::node::Node<'core>) -> Crop<'core> {"));
+        assert!(source.contains("pub fn r#loop(&self, clip: ::rustsynth::node::Node<'core>)"));
+        assert!(source.contains("pub fn left(mut self, left: i64) -> Self {"));
+        assert!(source.contains(
+            "pub fn call(self) -> ::rustsynth::plugin::PluginResult<::rustsynth::node::Node<'core>> {"
+        ));
+        assert!(source.contains("expr: ::std::vec::Vec<::std::string::String>) -> Expr<'core> {"));
+        assert!(source.contains("let out = self.plugin.try_invoke(\"Crop\", &args)?;"));
+    }
+
+    #[test]
+    fn test_generate_golden() {
+        // Regenerate golden.rs from golden_spec() when the output changes on purpose
+        let source = BindingGenerator::new().generate_from_specs(&[golden_spec()]);
+        assert_eq!(source, include_str!("golden.rs"));
+    }
+
+    #[test]
+    fn test_generate_namespace_filter() {
+        let mut other = std_spec();
+        other.namespace = "resize".to_owned();
+
+        let source = BindingGenerator::new()
+            .namespace("resize")
+            .generate_from_specs(&[std_spec(), other]);
+        assert!(source.contains("pub mod resize {"));
+        assert!(!source.contains("pub mod std {"));
+    }
+}
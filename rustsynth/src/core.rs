@@ -7,14 +7,16 @@ use crate::{
     log::{log_handler_callback, LogHandle, LogHandler, MessageType},
     map::{Map, MapError},
     node::Node,
-    plugin::Plugin,
+    plugin::{Plugin, PluginError},
 };
 use bitflags::bitflags;
 use rustsynth_sys as ffi;
 use std::fmt;
 use std::{
+    collections::HashSet,
     ffi::{CStr, CString, NulError},
     marker::PhantomData,
+    path::{Path, PathBuf},
     ptr::NonNull,
 };
 use thiserror::Error;
@@ -40,8 +42,48 @@ pub enum CoreError {
 /// A specialized `Result` type for `Core` operations.
 pub type CoreResult<T> = Result<T, CoreError>;
 
+/// The error type for [`CoreRef::load_plugin()`] and [`CoreRef::load_plugin_dir()`].
+#[derive(Error, Debug)]
+pub enum LoadPluginError {
+    #[error("Plugin path not found: {}", _0.display())]
+    NotFound(PathBuf),
+    #[error("Plugin path is not valid UTF-8: {}", _0.display())]
+    InvalidPath(PathBuf),
+    /// A plugin with the same identifier or namespace is loaded already.
+    #[error("Plugin is already loaded: {0}")]
+    AlreadyLoaded(String),
+    /// The file loaded without error but didn't add a plugin to the core.
+    #[error("Loading {} added no plugin", _0.display())]
+    NoPluginAdded(PathBuf),
+    #[error("Plugin requires an unsupported API version: {0}")]
+    UnsupportedApiVersion(String),
+    #[error("Failed to load plugin: {0}")]
+    LoadFailed(String),
+    #[error("The std plugin is not available")]
+    StdNotFound,
+    #[error("Argument map error: {0}")]
+    Map(#[from] MapError),
+}
+
+impl LoadPluginError {
+    /// Sorts an error message of `std.LoadPlugin` into a variant by the messages `VSCore` throws.
+    fn from_message(message: String) -> Self {
+        let duplicate_id = message.starts_with("Plugin ") && message.contains(" already loaded (");
+        let duplicate_namespace =
+            message.starts_with("Plugin load of ") && message.contains(" already populated");
+        if duplicate_id || duplicate_namespace {
+            Self::AlreadyLoaded(message)
+        } else if message.starts_with("Core only supports API R") {
+            Self::UnsupportedApiVersion(message)
+        } else {
+            Self::LoadFailed(message)
+        }
+    }
+}
+
 bitflags! {
     /// Options when creating a core.
+    #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
     pub struct CoreCreationFlags: i32 {
         /// No flags.
         const NONE = 0;
@@ -175,6 +217,70 @@ impl<'core> CoreRef<'core> {
         unsafe { API::get_cached() }.plugins(self)
     }
 
+    /// Loads the plugin at `path` and returns it.
+    ///
+    /// Useful together with [`CoreBuilder::disable_auto_loading()`] to run against a known set of
+    /// plugins.
+    pub fn load_plugin(&self, path: impl AsRef<Path>) -> Result<Plugin<'core>, LoadPluginError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(LoadPluginError::NotFound(path.to_owned()));
+        }
+
+        let loaded = self.load_with("LoadPlugin", path)?;
+        loaded
+            .into_iter()
+            .next()
+            .ok_or_else(|| LoadPluginError::NoPluginAdded(path.to_owned()))
+    }
+
+    /// Loads every plugin in the directory `dir` and returns the ones that weren't loaded before.
+    ///
+    /// Files that fail to load are skipped.
+    pub fn load_plugin_dir(
+        &self,
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<Plugin<'core>>, LoadPluginError> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(LoadPluginError::NotFound(dir.to_owned()));
+        }
+        self.load_with("LoadAllPlugins", dir)
+    }
+
+    /// Invokes `std.<function>` with `path` and returns the plugins that appeared.
+    fn load_with(
+        &self,
+        function: &str,
+        path: &Path,
+    ) -> Result<Vec<Plugin<'core>>, LoadPluginError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| LoadPluginError::InvalidPath(path.to_owned()))?;
+        let std = self
+            .plugin_by_namespace("std")
+            .ok_or(LoadPluginError::StdNotFound)?;
+
+        let before: HashSet<_> = self.plugins().map(|plugin| plugin.as_ptr()).collect();
+        let mut args = Map::new()?;
+        args.set_string("path", path_str)?;
+        match std.try_invoke(function, &args) {
+            Ok(_) => {}
+            Err(PluginError::InvokeError(message)) => {
+                return Err(LoadPluginError::from_message(message))
+            }
+            Err(error) => return Err(LoadPluginError::LoadFailed(error.to_string())),
+        }
+
+        Ok(self
+            .plugins()
+            .map(|plugin| plugin.as_ptr())
+            .filter(|ptr| !before.contains(ptr))
+            // SAFETY: plugins stay loaded for the lifetime of the core
+            .map(|ptr| unsafe { Plugin::from_ptr(ptr) })
+            .collect())
+    }
+
     /// Sets the number of threads used for processing. Pass 0 to automatically detect. Returns the number of threads that will be used for processing.
     #[inline]
     #[must_use]
@@ -188,6 +294,7 @@ impl<'core> CoreRef<'core> {
     ///
     /// Must ensure that all frame requests have completed and all objects belonging to the core have been released.
     pub unsafe fn free_core(self) {
+        crate::plugin::hand_over_registered_functions(&self);
         API::get_cached().free_core(self.handle.as_ptr());
     }
 
@@ -220,6 +327,7 @@ impl<'core> CoreRef<'core> {
         let ptr = unsafe {
             API::get_cached().add_log_handler(
                 log_handler_callback,
+                None,
                 handler_ptr,
                 self.handle.as_ptr(),
             )
@@ -589,8 +697,11 @@ impl fmt::Display for CoreInfo {
 }
 
 /// Builder for creating a [`CoreRef`] with custom options.
+#[derive(Debug, Clone)]
 pub struct CoreBuilder {
     flags: CoreCreationFlags,
+    thread_count: Option<usize>,
+    max_cache_size: Option<i64>,
 }
 
 impl<'core> CoreBuilder {
@@ -599,6 +710,8 @@ impl<'core> CoreBuilder {
     pub const fn new() -> Self {
         Self {
             flags: CoreCreationFlags::NONE,
+            thread_count: None,
+            max_cache_size: None,
         }
     }
 
@@ -623,10 +736,32 @@ impl<'core> CoreBuilder {
         self
     }
 
+    /// Sets the number of threads used for processing, see [`CoreRef::set_thread_count()`].
+    #[must_use]
+    pub const fn with_thread_count(mut self, count: usize) -> Self {
+        self.thread_count = Some(count);
+        self
+    }
+
+    /// Sets the maximum size of the framebuffer cache in bytes, see
+    /// [`CoreRef::set_max_cache_size()`].
+    #[must_use]
+    pub const fn with_max_cache_size(mut self, size: i64) -> Self {
+        self.max_cache_size = Some(size);
+        self
+    }
+
     /// Builds and returns a [`CoreRef`].
     #[must_use]
     pub fn build(self) -> CoreRef<'core> {
-        CoreRef::new(self.flags)
+        let core = CoreRef::new(self.flags);
+        if let Some(count) = self.thread_count {
+            let _ = core.set_thread_count(count);
+        }
+        if let Some(size) = self.max_cache_size {
+            let _ = core.set_max_cache_size(size);
+        }
+        core
     }
 }
 
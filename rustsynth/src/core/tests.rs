@@ -3,7 +3,7 @@ mod tests {
     use crate::ffi::VAPOURSYNTH_API_VERSION;
 
     use crate::api::API;
-    use crate::core::{CoreCreationFlags, CoreRef};
+    use crate::core::{CoreBuilder, CoreCreationFlags, CoreRef, LoadPluginError};
 
     fn setup_api() -> API {
         API::get().expect("Failed to get VapourSynth API")
@@ -56,4 +56,56 @@ mod tests {
         );
         // If we get here without panicking, all core creations succeeded
     }
+
+    #[test]
+    fn test_load_plugin_errors() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+
+        let missing = std::env::temp_dir().join(format!(
+            "rustsynth-missing-plugin-{}.so",
+            std::process::id()
+        ));
+        assert!(matches!(
+            core.load_plugin(&missing),
+            Err(LoadPluginError::NotFound(path)) if path == missing
+        ));
+
+        let not_a_plugin =
+            std::env::temp_dir().join(format!("rustsynth-not-a-plugin-{}.so", std::process::id()));
+        std::fs::write(&not_a_plugin, b"not a shared library").unwrap();
+        assert!(matches!(
+            core.load_plugin(&not_a_plugin),
+            Err(LoadPluginError::LoadFailed(_))
+        ));
+        std::fs::remove_file(&not_a_plugin).unwrap();
+
+        let empty_dir =
+            std::env::temp_dir().join(format!("rustsynth-empty-plugin-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&empty_dir).unwrap();
+        assert!(core.load_plugin_dir(&empty_dir).unwrap().is_empty());
+        std::fs::remove_dir(&empty_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_plugin_error_messages() {
+        let error = |message: &str| LoadPluginError::from_message(message.to_owned());
+        assert!(matches!(
+            error("Plugin /usr/lib/libfoo.so already loaded (com.example.foo) from /opt/libfoo.so"),
+            LoadPluginError::AlreadyLoaded(_)
+        ));
+        assert!(matches!(
+            error("Plugin load of /usr/lib/libfoo.so failed, namespace foo already populated"),
+            LoadPluginError::AlreadyLoaded(_)
+        ));
+        assert!(matches!(
+            error("Core only supports API R4.1 but the loaded plugin requires API R5.0; Filename: libfoo.so; Name: Foo"),
+            LoadPluginError::UnsupportedApiVersion(_)
+        ));
+        // Only the messages of the core are matched, not words a plugin path may contain
+        assert!(matches!(
+            error("Failed to load /home/API/already loaded/libfoo.so. Error given: invalid ELF header"),
+            LoadPluginError::LoadFailed(_)
+        ));
+    }
 }
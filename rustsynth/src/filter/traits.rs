@@ -9,6 +9,7 @@ use crate::{
 /// Trait that filter structs must implement
 pub trait Filter<'core>: Send + Sync + Clone + 'core {
     const NAME: &'static str;
+    /// The argument signature, e.g. `clip:vnode;planes:int[]:opt;`. See [`crate::signature`].
     const ARGS: &'static str;
     const RETURNTYPE: &'static str;
     const MODE: FilterMode;
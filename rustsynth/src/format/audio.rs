@@ -1,8 +1,9 @@
 use rustsynth_sys as ffi;
+use std::{fmt, str::FromStr};
 
 use crate::{
     api::API,
-    format::{ChannelLayout, FormatError, SampleType},
+    format::{ChannelLayout, FormatError, FormatMappingError, FormatNamingScheme, SampleType},
 };
 
 /// Builder for creating `AudioFormat` with validation
@@ -49,6 +50,13 @@ impl AudioFormatBuilder {
         self
     }
 
+    /// Set the channel layout by its FFmpeg name such as `5.1(side)`, see
+    /// [`ChannelLayout::from_ffmpeg_name()`]
+    pub fn named_layout(mut self, name: &str) -> Result<Self, FormatMappingError> {
+        self.channel_layout = ChannelLayout::from_ffmpeg_name(name)?;
+        Ok(self)
+    }
+
     /// Build the `AudioFormat` using `VapourSynth`'s validation
     pub fn build(self, core: &crate::core::CoreRef) -> Result<AudioFormat, FormatError> {
         AudioFormat::query(
@@ -60,6 +68,20 @@ impl AudioFormatBuilder {
     }
 }
 
+impl FromStr for AudioFormatBuilder {
+    type Err = FormatMappingError;
+
+    /// Parses the same descriptions as [`AudioFormat`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format: AudioFormat = s.parse()?;
+        Ok(Self::new(
+            format.sample_type,
+            format.bits_per_sample,
+            format.channel_layout,
+        ))
+    }
+}
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub struct AudioInfo {
     pub format: AudioFormat,
@@ -77,6 +99,15 @@ pub struct AudioFormat {
 }
 
 impl AudioInfo {
+    /// The number of samples in every audio frame but the last.
+    pub const FRAME_SAMPLES: i64 = ffi::VS_AUDIO_FRAME_SAMPLES as i64;
+
+    /// The frame holding the sample at `sample`.
+    #[must_use]
+    pub const fn frame_of_sample(sample: i64) -> i32 {
+        (sample / Self::FRAME_SAMPLES) as i32
+    }
+
     /// # Safety
     /// The pointer must be valid and point to a [`ffi::VSAudioInfo`]
     #[must_use]
@@ -122,6 +153,23 @@ impl AudioFormat {
         }
     }
 
+    /// Creates an `AudioFormat` from its defining fields, filling in the derived ones without asking
+    /// the core. The result is not validated.
+    #[must_use]
+    pub const fn from_parts(
+        sample_type: SampleType,
+        bits_per_sample: i32,
+        channel_layout: ChannelLayout,
+    ) -> Self {
+        Self {
+            sample_type,
+            bits_per_sample,
+            bytes_per_sample: if bits_per_sample <= 16 { 2 } else { 4 },
+            num_channels: channel_layout.channel_count() as i32,
+            channel_layout,
+        }
+    }
+
     /// Creates an `AudioFormat` using `VapourSynth`'s validation.
     /// This ensures all derived fields are correctly calculated.
     pub fn query(
@@ -195,3 +243,55 @@ impl AudioFormat {
         channel_layout: ChannelLayout::MONO,
     };
 }
+
+impl fmt::Display for AudioFormat {
+    /// Writes a description such as `s16:stereo` or `f32:FL+FR+LFE` that parses back to the same
+    /// format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sample = match self.sample_type {
+            SampleType::Integer => 's',
+            SampleType::Float => 'f',
+        };
+        write!(
+            f,
+            "{sample}{}:{}",
+            self.bits_per_sample, self.channel_layout
+        )
+    }
+}
+
+impl FromStr for AudioFormat {
+    type Err = FormatMappingError;
+
+    /// Parses a description such as `s16`, `s24:5.1` or `f32:0x3`.
+    ///
+    /// The sample type is `s` for integer and `f` for float samples, followed by the bits per
+    /// sample. The layout is parsed by [`ChannelLayout::from_ffmpeg_name()`] and defaults to
+    /// stereo. The result is not validated by the core, use
+    /// [`AudioFormatBuilder`] for that.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || FormatMappingError::UnknownName {
+            name: s.to_owned(),
+            scheme: FormatNamingScheme::AudioDescription,
+        };
+        let (sample, layout) = s.split_once(':').unwrap_or((s, "stereo"));
+        let sample = sample.to_ascii_lowercase();
+
+        let sample_type = match sample.as_bytes().first() {
+            Some(b's') => SampleType::Integer,
+            Some(b'f') => SampleType::Float,
+            _ => return Err(error()),
+        };
+        let bits_per_sample: i32 = sample[1..].parse().map_err(|_| error())?;
+        if !(8..=32).contains(&bits_per_sample) {
+            return Err(error());
+        }
+
+        let channel_layout = ChannelLayout::from_ffmpeg_name(layout).map_err(|_| error())?;
+        Ok(Self::from_parts(
+            sample_type,
+            bits_per_sample,
+            channel_layout,
+        ))
+    }
+}
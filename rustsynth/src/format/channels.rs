@@ -0,0 +1,182 @@
+use std::{fmt, str::FromStr};
+
+use crate::format::{ChannelLayout, FormatMappingError, FormatNamingScheme};
+
+/// FFmpeg's abbreviations of the channels, `VapourSynth` uses the same bit order.
+const CHANNEL_NAMES: &[(&str, ChannelLayout)] = &[
+    ("FL", ChannelLayout::FRONT_LEFT),
+    ("FR", ChannelLayout::FRONT_RIGHT),
+    ("FC", ChannelLayout::FRONT_CENTER),
+    ("LFE", ChannelLayout::LOW_FREQUENCY),
+    ("BL", ChannelLayout::BACK_LEFT),
+    ("BR", ChannelLayout::BACK_RIGHT),
+    ("FLC", ChannelLayout::FRONT_LEFT_OF_CENTER),
+    ("FRC", ChannelLayout::FRONT_RIGHT_OF_CENTER),
+    ("BC", ChannelLayout::BACK_CENTER),
+    ("SL", ChannelLayout::SIDE_LEFT),
+    ("SR", ChannelLayout::SIDE_RIGHT),
+    ("TC", ChannelLayout::TOP_CENTER),
+    ("TFL", ChannelLayout::TOP_FRONT_LEFT),
+    ("TFC", ChannelLayout::TOP_FRONT_CENTER),
+    ("TFR", ChannelLayout::TOP_FRONT_RIGHT),
+    ("TBL", ChannelLayout::TOP_BACK_LEFT),
+    ("TBC", ChannelLayout::TOP_BACK_CENTER),
+    ("TBR", ChannelLayout::TOP_BACK_RIGHT),
+    ("DL", ChannelLayout::STEREO_LEFT),
+    ("DR", ChannelLayout::STEREO_RIGHT),
+    ("WL", ChannelLayout::WIDE_LEFT),
+    ("WR", ChannelLayout::WIDE_RIGHT),
+    ("SDL", ChannelLayout::SURROUND_DIRECT_LEFT),
+    ("SDR", ChannelLayout::SURROUND_DIRECT_RIGHT),
+    ("LFE2", ChannelLayout::LOW_FREQUENCY_2),
+];
+
+/// FFmpeg's names of the standard layouts.
+const LAYOUT_NAMES: &[(&str, ChannelLayout)] = &[
+    ("mono", ChannelLayout::MONO),
+    ("stereo", ChannelLayout::STEREO),
+    ("2.1", ChannelLayout::SURROUND_2_1),
+    ("3.0", ChannelLayout::SURROUND_3_0),
+    ("3.0(back)", ChannelLayout::SURROUND_3_0_BACK),
+    (
+        "4.0",
+        ChannelLayout::SURROUND_3_0.union(ChannelLayout::BACK_CENTER),
+    ),
+    ("quad", ChannelLayout::QUAD),
+    ("quad(side)", ChannelLayout::QUAD_SIDE),
+    ("3.1", ChannelLayout::SURROUND_3_1),
+    ("5.0", ChannelLayout::SURROUND_5_0),
+    ("5.0(side)", ChannelLayout::SURROUND_5_0_SIDE),
+    (
+        "4.1",
+        ChannelLayout::SURROUND_3_1.union(ChannelLayout::BACK_CENTER),
+    ),
+    ("5.1", ChannelLayout::SURROUND_5_1),
+    ("5.1(side)", ChannelLayout::SURROUND_5_1_SIDE),
+    ("6.0", ChannelLayout::SURROUND_6_0),
+    ("hexagonal", ChannelLayout::HEXAGONAL),
+    ("6.1", ChannelLayout::SURROUND_6_1),
+    ("7.0", ChannelLayout::SURROUND_7_0),
+    ("7.1", ChannelLayout::SURROUND_7_1),
+    ("7.1(wide)", ChannelLayout::SURROUND_7_1_WIDE),
+    ("7.1(wide-side)", ChannelLayout::SURROUND_7_1_WIDE_SIDE),
+    ("octagonal", ChannelLayout::OCTAGONAL),
+    ("downmix", ChannelLayout::DOWNMIX),
+];
+
+/// The channels that have a `SPEAKER_*` bit in `dwChannelMask`, in the same positions.
+const WAVE_CHANNELS: u64 = 0x3ffff;
+
+/// `SPEAKER_ALL`, which doesn't describe any positions.
+const WAVE_SPEAKER_ALL: u32 = 0x8000_0000;
+
+impl ChannelLayout {
+    /// The `dwChannelMask` of a `WAVE_FORMAT_EXTENSIBLE` header.
+    ///
+    /// The first 18 channels share their bit positions with the `SPEAKER_*` constants, the others
+    /// have no equivalent.
+    pub fn wave_channel_mask(self) -> Result<u32, FormatMappingError> {
+        if self.bits() & !WAVE_CHANNELS == 0 {
+            Ok(self.bits() as u32)
+        } else {
+            Err(FormatMappingError::NoChannelMapping {
+                layout: self,
+                scheme: FormatNamingScheme::WaveChannelMask,
+            })
+        }
+    }
+
+    /// The layout described by the `dwChannelMask` of a `WAVE_FORMAT_EXTENSIBLE` header.
+    ///
+    /// Reserved bits and `SPEAKER_ALL` are errors, since they don't say where the channels go.
+    pub fn from_wave_channel_mask(mask: u32) -> Result<Self, FormatMappingError> {
+        if mask != WAVE_SPEAKER_ALL && u64::from(mask) & !WAVE_CHANNELS == 0 {
+            Ok(Self::from_bits_truncate(u64::from(mask)))
+        } else {
+            Err(FormatMappingError::UnknownName {
+                name: format!("{mask:#x}"),
+                scheme: FormatNamingScheme::WaveChannelMask,
+            })
+        }
+    }
+
+    /// The FFmpeg name of the layout, such as `5.1(side)`, if it is a standard layout.
+    ///
+    /// `VapourSynth` puts a single channel on the front left while FFmpeg puts it in the front
+    /// center, both are called `mono`.
+    #[must_use]
+    pub fn ffmpeg_name(self) -> Option<&'static str> {
+        if self == Self::FRONT_CENTER {
+            return Some("mono");
+        }
+        LAYOUT_NAMES
+            .iter()
+            .find(|(_, layout)| *layout == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// Parses an FFmpeg layout description.
+    ///
+    /// This is a standard layout name such as `stereo` or `7.1`, channel abbreviations joined by `+`
+    /// such as `FL+FR+LFE`, or a hexadecimal channel mask such as `0x3f`. The empty layout is
+    /// `0x0`.
+    pub fn from_ffmpeg_name(name: &str) -> Result<Self, FormatMappingError> {
+        let error = || FormatMappingError::UnknownName {
+            name: name.to_owned(),
+            scheme: FormatNamingScheme::ChannelLayout,
+        };
+        if let Some((_, layout)) = LAYOUT_NAMES
+            .iter()
+            .find(|(layout_name, _)| layout_name.eq_ignore_ascii_case(name))
+        {
+            return Ok(*layout);
+        }
+        if let Some(mask) = name.strip_prefix("0x") {
+            return u64::from_str_radix(mask, 16)
+                .ok()
+                .map(Self::from_bits_truncate)
+                .ok_or_else(error);
+        }
+        name.split('+').try_fold(Self::empty(), |layout, channel| {
+            CHANNEL_NAMES
+                .iter()
+                .find(|(channel_name, _)| channel_name.eq_ignore_ascii_case(channel))
+                .map(|(_, bit)| layout | *bit)
+                .ok_or_else(error)
+        })
+    }
+}
+
+impl fmt::Display for ChannelLayout {
+    /// Writes the FFmpeg layout name, or the channels joined by `+` if the layout has no name.
+    ///
+    /// The empty layout is written as `0x0`, which [`ChannelLayout::from_ffmpeg_name()`] accepts.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = LAYOUT_NAMES
+            .iter()
+            .find(|(_, layout)| layout == self)
+            .map(|(name, _)| *name)
+        {
+            return f.write_str(name);
+        }
+        let channels: Vec<_> = CHANNEL_NAMES
+            .iter()
+            .filter(|(_, bit)| self.contains(*bit))
+            .map(|(name, _)| *name)
+            .collect();
+        if channels.is_empty() {
+            f.write_str("0x0")
+        } else {
+            f.write_str(&channels.join("+"))
+        }
+    }
+}
+
+impl FromStr for ChannelLayout {
+    type Err = FormatMappingError;
+
+    /// Same as [`ChannelLayout::from_ffmpeg_name()`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_ffmpeg_name(s)
+    }
+}
@@ -0,0 +1,102 @@
+use std::ops::Sub;
+
+#[cfg(feature = "f16-pixel-type")]
+use half::f16;
+
+use crate::format::{Component, FormatError, SampleType, VideoFormat};
+
+/// A pixel component with the arithmetic filters commonly need.
+pub trait Sample:
+    Component + Copy + PartialOrd + Sub<Output = Self> + Into<f64> + Send + Sync + 'static
+{
+    /// The value zero.
+    const ZERO: Self;
+
+    /// The largest value of a sample with `bits_per_sample` significant bits, 1.0 for floats.
+    fn peak(bits_per_sample: i32) -> Self;
+}
+
+macro_rules! impl_integer_sample {
+    ($($ty:ty),*) => {
+        $(
+            impl Sample for $ty {
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn peak(bits_per_sample: i32) -> Self {
+                    <$ty>::MAX >> (<$ty>::BITS - bits_per_sample as u32)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_sample!(u8, u16, u32);
+
+#[cfg(feature = "f16-pixel-type")]
+impl Sample for f16 {
+    const ZERO: Self = f16::ZERO;
+
+    #[inline]
+    fn peak(_bits_per_sample: i32) -> Self {
+        f16::ONE
+    }
+}
+
+impl Sample for f32 {
+    const ZERO: Self = 0.0;
+
+    #[inline]
+    fn peak(_bits_per_sample: i32) -> Self {
+        1.0
+    }
+}
+
+/// An operation that is generic over the sample type, run by [`VideoFormat::dispatch()`].
+///
+/// # Example
+/// ```
+/// use rustsynth::format::{ComponentVisitor, Sample, VideoFormat};
+///
+/// struct Peak;
+///
+/// impl ComponentVisitor for Peak {
+///     type Output = f64;
+///
+///     fn visit<T: Sample>(self, bits_per_sample: i32) -> f64 {
+///         T::peak(bits_per_sample).into()
+///     }
+/// }
+///
+/// let format: VideoFormat = "yuv420p10".parse().unwrap();
+/// assert_eq!(format.dispatch(Peak), Ok(1023.0));
+/// ```
+pub trait ComponentVisitor {
+    type Output;
+
+    /// Runs the operation with `T` as the sample type of the format.
+    fn visit<T: Sample>(self, bits_per_sample: i32) -> Self::Output;
+}
+
+impl VideoFormat {
+    /// Calls `visitor` with the [`Sample`] type matching the format: `u8`, `u16` or `u32` for
+    /// integer and `f16` or `f32` for float formats.
+    ///
+    /// Half precision formats need the `f16-pixel-type` feature, without it they return
+    /// [`FormatError::UnsupportedSample`] as do formats that are undefined.
+    pub fn dispatch<V: ComponentVisitor>(&self, visitor: V) -> Result<V::Output, FormatError> {
+        let bits = self.bits_per_sample;
+        match (self.sample_type, self.bytes_per_sample) {
+            (SampleType::Integer, 1) => Ok(visitor.visit::<u8>(bits)),
+            (SampleType::Integer, 2) => Ok(visitor.visit::<u16>(bits)),
+            (SampleType::Integer, 4) => Ok(visitor.visit::<u32>(bits)),
+            #[cfg(feature = "f16-pixel-type")]
+            (SampleType::Float, 2) => Ok(visitor.visit::<f16>(bits)),
+            (SampleType::Float, 4) => Ok(visitor.visit::<f32>(bits)),
+            (sample_type, bytes_per_sample) => Err(FormatError::UnsupportedSample {
+                sample_type,
+                bytes_per_sample,
+            }),
+        }
+    }
+}
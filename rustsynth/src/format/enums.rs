@@ -1,5 +1,4 @@
 use bitflags::bitflags;
-use std::fmt::Display;
 
 use rustsynth_sys as ffi;
 
@@ -52,10 +51,12 @@ impl ChannelLayout {
     pub const SURROUND_3_0: Self = Self::FRONT_LEFT
         .union(Self::FRONT_RIGHT)
         .union(Self::FRONT_CENTER);
+    /// FFmpeg's `quad`
     pub const SURROUND_4_0: Self = Self::FRONT_LEFT
         .union(Self::FRONT_RIGHT)
         .union(Self::BACK_LEFT)
         .union(Self::BACK_RIGHT);
+    /// FFmpeg's `quad` with a low frequency channel, which is not FFmpeg's `4.1`
     pub const SURROUND_4_1: Self = Self::FRONT_LEFT
         .union(Self::FRONT_RIGHT)
         .union(Self::BACK_LEFT)
@@ -81,6 +82,30 @@ impl ChannelLayout {
         .union(Self::SIDE_LEFT)
         .union(Self::SIDE_RIGHT);
 
+    // The other layouts with an FFmpeg name
+    pub const SURROUND_3_0_BACK: Self = Self::STEREO.union(Self::BACK_CENTER);
+    pub const SURROUND_3_1: Self = Self::SURROUND_3_0.union(Self::LOW_FREQUENCY);
+    pub const QUAD: Self = Self::SURROUND_4_0;
+    pub const QUAD_SIDE: Self = Self::STEREO.union(Self::SIDE_LEFT).union(Self::SIDE_RIGHT);
+    pub const SURROUND_5_0_SIDE: Self = Self::SURROUND_3_0
+        .union(Self::SIDE_LEFT)
+        .union(Self::SIDE_RIGHT);
+    pub const SURROUND_5_1_SIDE: Self = Self::SURROUND_5_0_SIDE.union(Self::LOW_FREQUENCY);
+    pub const SURROUND_6_0: Self = Self::SURROUND_5_0_SIDE.union(Self::BACK_CENTER);
+    pub const SURROUND_6_1: Self = Self::SURROUND_5_1_SIDE.union(Self::BACK_CENTER);
+    pub const SURROUND_7_0: Self = Self::SURROUND_5_0_SIDE
+        .union(Self::BACK_LEFT)
+        .union(Self::BACK_RIGHT);
+    pub const SURROUND_7_1_WIDE: Self = Self::SURROUND_5_1
+        .union(Self::FRONT_LEFT_OF_CENTER)
+        .union(Self::FRONT_RIGHT_OF_CENTER);
+    pub const SURROUND_7_1_WIDE_SIDE: Self = Self::SURROUND_5_1_SIDE
+        .union(Self::FRONT_LEFT_OF_CENTER)
+        .union(Self::FRONT_RIGHT_OF_CENTER);
+    pub const HEXAGONAL: Self = Self::SURROUND_5_0.union(Self::BACK_CENTER);
+    pub const OCTAGONAL: Self = Self::SURROUND_7_0.union(Self::BACK_CENTER);
+    pub const DOWNMIX: Self = Self::STEREO_LEFT.union(Self::STEREO_RIGHT);
+
     /// Create a new empty channel layout
     #[must_use]
     pub const fn new() -> Self {
@@ -112,19 +137,6 @@ impl ChannelLayout {
     }
 }
 
-impl Display for ChannelLayout {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut channels = Vec::new();
-        for i in 0..64 {
-            let channel = Self::from_bits_truncate(1u64 << i);
-            if self.contains(channel) {
-                channels.push(format!("ch{i}"));
-            }
-        }
-        write!(f, "ChannelLayout({})", channels.join(", "))
-    }
-}
-
 impl Default for ChannelLayout {
     fn default() -> Self {
         Self::empty()
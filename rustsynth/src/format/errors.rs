@@ -1,4 +1,4 @@
-use crate::format::{ColorFamily, SampleType};
+use crate::format::{ChannelLayout, ColorFamily, SampleType, VideoFormat};
 
 /// Errors that can occur when creating formats
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,6 +17,11 @@ pub enum FormatError {
         bits_per_sample: i32,
         channel_layout: u64,
     },
+    /// No sample type for the sample type and size
+    UnsupportedSample {
+        sample_type: SampleType,
+        bytes_per_sample: i32,
+    },
 }
 
 impl std::fmt::Display for FormatError {
@@ -40,8 +45,85 @@ impl std::fmt::Display for FormatError {
                 f,
                 "Invalid audio format: sample_type={sample_type:?}, bits_per_sample={bits_per_sample}, channel_layout={channel_layout}"
             ),
+            Self::UnsupportedSample {
+                sample_type,
+                bytes_per_sample,
+            } => write!(
+                f,
+                "Unsupported sample: sample_type={sample_type:?}, bytes_per_sample={bytes_per_sample}"
+            ),
         }
     }
 }
 
 impl std::error::Error for FormatError {}
+
+/// The naming schemes for formats used by other tools and by the string conversions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatNamingScheme {
+    /// Y4M colorspace tags
+    Y4m,
+    /// FFmpeg `pix_fmt` names
+    PixFmt,
+    /// FourCCs
+    FourCc,
+    /// [`PresetVideoFormat`](crate::format::PresetVideoFormat) names
+    Preset,
+    /// Video format names and descriptions such as `yuv420p10`
+    VideoDescription,
+    /// Audio format descriptions such as `s16:stereo`
+    AudioDescription,
+    /// The `dwChannelMask` of WAVE headers
+    WaveChannelMask,
+    /// FFmpeg channel layout names such as `5.1(side)`
+    ChannelLayout,
+}
+
+impl std::fmt::Display for FormatNamingScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Y4m => "Y4M colorspace",
+            Self::PixFmt => "FFmpeg pix_fmt",
+            Self::FourCc => "FourCC",
+            Self::Preset => "preset format",
+            Self::VideoDescription => "video format",
+            Self::AudioDescription => "audio format",
+            Self::WaveChannelMask => "WAVE channel mask",
+            Self::ChannelLayout => "channel layout",
+        })
+    }
+}
+
+/// Errors that can occur when mapping formats to and from the names used by other tools
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatMappingError {
+    /// The format has no name in the scheme
+    NoMapping {
+        format: VideoFormat,
+        scheme: FormatNamingScheme,
+    },
+    /// The channel layout has no equivalent in the scheme
+    NoChannelMapping {
+        layout: ChannelLayout,
+        scheme: FormatNamingScheme,
+    },
+    /// The name does not describe a known format
+    UnknownName {
+        name: String,
+        scheme: FormatNamingScheme,
+    },
+}
+
+impl std::fmt::Display for FormatMappingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoMapping { format, scheme } => write!(f, "No {scheme} for {format}"),
+            Self::NoChannelMapping { layout, scheme } => {
+                write!(f, "No {scheme} for channel layout {layout}")
+            }
+            Self::UnknownName { name, scheme } => write!(f, "Unknown {scheme} '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for FormatMappingError {}
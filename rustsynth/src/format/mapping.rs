@@ -0,0 +1,180 @@
+use crate::format::{ColorFamily, FormatMappingError, FormatNamingScheme, SampleType, VideoFormat};
+
+/// A video format and its names in the other tools, `None` where it has none.
+struct Entry {
+    color_family: ColorFamily,
+    sample_type: SampleType,
+    bits_per_sample: i32,
+    sub_sampling_w: i32,
+    sub_sampling_h: i32,
+    y4m: Option<&'static str>,
+    pix_fmt: Option<&'static str>,
+    fourcc: Option<[u8; 4]>,
+}
+
+impl Entry {
+    const fn matches(&self, format: &VideoFormat) -> bool {
+        self.color_family as i32 == format.color_family as i32
+            && self.sample_type as i32 == format.sample_type as i32
+            && self.bits_per_sample == format.bits_per_sample
+            && self.sub_sampling_w == format.sub_sampling_w
+            && self.sub_sampling_h == format.sub_sampling_h
+    }
+
+    const fn format(&self) -> VideoFormat {
+        VideoFormat::from_parts(
+            self.color_family,
+            self.sample_type,
+            self.bits_per_sample,
+            self.sub_sampling_w,
+            self.sub_sampling_h,
+        )
+    }
+}
+
+macro_rules! entry {
+    ($cf:ident, $st:ident, $bits:expr, $ssw:expr, $ssh:expr, $y4m:expr, $pix_fmt:expr, $fourcc:expr) => {
+        Entry {
+            color_family: ColorFamily::$cf,
+            sample_type: SampleType::$st,
+            bits_per_sample: $bits,
+            sub_sampling_w: $ssw,
+            sub_sampling_h: $ssh,
+            y4m: $y4m,
+            pix_fmt: $pix_fmt,
+            fourcc: $fourcc,
+        }
+    };
+}
+
+#[rustfmt::skip]
+static FORMATS: &[Entry] = &[
+    entry!(Gray, Integer, 8, 0, 0, Some("mono"), Some("gray"), Some(*b"Y800")),
+    entry!(Gray, Integer, 9, 0, 0, Some("mono9"), Some("gray9le"), None),
+    entry!(Gray, Integer, 10, 0, 0, Some("mono10"), Some("gray10le"), None),
+    entry!(Gray, Integer, 12, 0, 0, Some("mono12"), Some("gray12le"), None),
+    entry!(Gray, Integer, 14, 0, 0, Some("mono14"), Some("gray14le"), None),
+    entry!(Gray, Integer, 16, 0, 0, Some("mono16"), Some("gray16le"), Some(*b"Y16 ")),
+    entry!(Gray, Float, 32, 0, 0, None, Some("grayf32le"), None),
+
+    entry!(YUV, Integer, 8, 1, 1, Some("420jpeg"), Some("yuv420p"), Some(*b"I420")),
+    entry!(YUV, Integer, 9, 1, 1, Some("420p9"), Some("yuv420p9le"), None),
+    entry!(YUV, Integer, 10, 1, 1, Some("420p10"), Some("yuv420p10le"), None),
+    entry!(YUV, Integer, 12, 1, 1, Some("420p12"), Some("yuv420p12le"), None),
+    entry!(YUV, Integer, 14, 1, 1, Some("420p14"), Some("yuv420p14le"), None),
+    entry!(YUV, Integer, 16, 1, 1, Some("420p16"), Some("yuv420p16le"), None),
+
+    entry!(YUV, Integer, 8, 1, 0, Some("422"), Some("yuv422p"), Some(*b"Y42B")),
+    entry!(YUV, Integer, 9, 1, 0, Some("422p9"), Some("yuv422p9le"), None),
+    entry!(YUV, Integer, 10, 1, 0, Some("422p10"), Some("yuv422p10le"), None),
+    entry!(YUV, Integer, 12, 1, 0, Some("422p12"), Some("yuv422p12le"), None),
+    entry!(YUV, Integer, 14, 1, 0, Some("422p14"), Some("yuv422p14le"), None),
+    entry!(YUV, Integer, 16, 1, 0, Some("422p16"), Some("yuv422p16le"), None),
+
+    entry!(YUV, Integer, 8, 0, 0, Some("444"), Some("yuv444p"), Some(*b"Y444")),
+    entry!(YUV, Integer, 9, 0, 0, Some("444p9"), Some("yuv444p9le"), None),
+    entry!(YUV, Integer, 10, 0, 0, Some("444p10"), Some("yuv444p10le"), None),
+    entry!(YUV, Integer, 12, 0, 0, Some("444p12"), Some("yuv444p12le"), None),
+    entry!(YUV, Integer, 14, 0, 0, Some("444p14"), Some("yuv444p14le"), None),
+    entry!(YUV, Integer, 16, 0, 0, Some("444p16"), Some("yuv444p16le"), None),
+
+    entry!(YUV, Integer, 8, 0, 1, None, Some("yuv440p"), None),
+    entry!(YUV, Integer, 10, 0, 1, None, Some("yuv440p10le"), None),
+    entry!(YUV, Integer, 12, 0, 1, None, Some("yuv440p12le"), None),
+    entry!(YUV, Integer, 8, 2, 0, Some("411"), Some("yuv411p"), Some(*b"Y41B")),
+    entry!(YUV, Integer, 8, 2, 2, None, Some("yuv410p"), Some(*b"YUV9")),
+
+    // VapourSynth stores RGB as planar G, B, R which is FFmpeg's gbrp
+    entry!(RGB, Integer, 8, 0, 0, None, Some("gbrp"), None),
+    entry!(RGB, Integer, 9, 0, 0, None, Some("gbrp9le"), None),
+    entry!(RGB, Integer, 10, 0, 0, None, Some("gbrp10le"), None),
+    entry!(RGB, Integer, 12, 0, 0, None, Some("gbrp12le"), None),
+    entry!(RGB, Integer, 14, 0, 0, None, Some("gbrp14le"), None),
+    entry!(RGB, Integer, 16, 0, 0, None, Some("gbrp16le"), None),
+    entry!(RGB, Float, 32, 0, 0, None, Some("gbrpf32le"), None),
+];
+
+/// Y4M tags that only differ in chroma siting from an entry in the table.
+const Y4M_ALIASES: &[(&str, &str)] = &[
+    ("420", "420jpeg"),
+    ("420mpeg2", "420jpeg"),
+    ("420paldv", "420jpeg"),
+];
+
+fn entry(format: &VideoFormat) -> Option<&'static Entry> {
+    FORMATS.iter().find(|entry| entry.matches(format))
+}
+
+impl VideoFormat {
+    /// The Y4M colorspace tag of the format, the value of the `C` header field such as `420p10`.
+    pub fn y4m_colorspace(&self) -> Result<&'static str, FormatMappingError> {
+        entry(self)
+            .and_then(|entry| entry.y4m)
+            .ok_or(FormatMappingError::NoMapping {
+                format: *self,
+                scheme: FormatNamingScheme::Y4m,
+            })
+    }
+
+    /// The format described by a Y4M colorspace tag, without the leading `C`.
+    ///
+    /// Tags that only set the chroma siting, such as `420mpeg2`, map to the same format.
+    pub fn from_y4m_colorspace(tag: &str) -> Result<Self, FormatMappingError> {
+        let tag = Y4M_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == tag)
+            .map_or(tag, |(_, canonical)| canonical);
+        FORMATS
+            .iter()
+            .find(|entry| entry.y4m == Some(tag))
+            .map(Entry::format)
+            .ok_or_else(|| FormatMappingError::UnknownName {
+                name: tag.to_owned(),
+                scheme: FormatNamingScheme::Y4m,
+            })
+    }
+
+    /// The FFmpeg `pix_fmt` name of the format, such as `yuv420p10le`.
+    pub fn pix_fmt(&self) -> Result<&'static str, FormatMappingError> {
+        entry(self)
+            .and_then(|entry| entry.pix_fmt)
+            .ok_or(FormatMappingError::NoMapping {
+                format: *self,
+                scheme: FormatNamingScheme::PixFmt,
+            })
+    }
+
+    /// The format described by an FFmpeg `pix_fmt` name.
+    pub fn from_pix_fmt(name: &str) -> Result<Self, FormatMappingError> {
+        FORMATS
+            .iter()
+            .find(|entry| entry.pix_fmt == Some(name))
+            .map(Entry::format)
+            .ok_or_else(|| FormatMappingError::UnknownName {
+                name: name.to_owned(),
+                scheme: FormatNamingScheme::PixFmt,
+            })
+    }
+
+    /// The FourCC of the format when its planes are written one after another.
+    pub fn fourcc(&self) -> Result<[u8; 4], FormatMappingError> {
+        entry(self)
+            .and_then(|entry| entry.fourcc)
+            .ok_or(FormatMappingError::NoMapping {
+                format: *self,
+                scheme: FormatNamingScheme::FourCc,
+            })
+    }
+
+    /// The format described by a FourCC.
+    pub fn from_fourcc(fourcc: [u8; 4]) -> Result<Self, FormatMappingError> {
+        FORMATS
+            .iter()
+            .find(|entry| entry.fourcc == Some(fourcc))
+            .map(Entry::format)
+            .ok_or_else(|| FormatMappingError::UnknownName {
+                name: String::from_utf8_lossy(&fourcc).into_owned(),
+                scheme: FormatNamingScheme::FourCc,
+            })
+    }
+}
@@ -1,7 +1,10 @@
 //! Module for audio and video format definitions and related functionality.
 mod audio;
+mod channels;
+mod dispatch;
 mod enums;
 mod errors;
+mod mapping;
 mod presets;
 mod video;
 
@@ -58,7 +61,8 @@ unsafe impl Component for f32 {
 }
 
 pub use audio::{AudioFormat, AudioFormatBuilder, AudioInfo};
+pub use dispatch::{ComponentVisitor, Sample};
 pub use enums::{ChannelLayout, ColorFamily, MediaType, SampleType};
-pub use errors::FormatError;
+pub use errors::{FormatError, FormatMappingError, FormatNamingScheme};
 pub use presets::PresetVideoFormat;
 pub use video::{VideoFormat, VideoFormatBuilder, VideoInfo};
@@ -1,6 +1,8 @@
+use std::{fmt, str::FromStr};
+
 use crate::{
     core::CoreRef,
-    format::{ColorFamily, SampleType, VideoFormat},
+    format::{ColorFamily, FormatMappingError, FormatNamingScheme, SampleType, VideoFormat},
 };
 /// Preset video format IDs as defined by `VapourSynth`.
 ///
@@ -82,9 +84,172 @@ const fn make_video_id(
 }
 
 impl PresetVideoFormat {
+    /// Every preset, in declaration order.
+    pub const ALL: &'static [Self] = &[
+        Self::None,
+        Self::Gray8,
+        Self::Gray9,
+        Self::Gray10,
+        Self::Gray12,
+        Self::Gray14,
+        Self::Gray16,
+        Self::Gray32,
+        Self::GrayH,
+        Self::GrayS,
+        Self::YUV410P8,
+        Self::YUV411P8,
+        Self::YUV440P8,
+        Self::YUV420P8,
+        Self::YUV422P8,
+        Self::YUV444P8,
+        Self::YUV420P9,
+        Self::YUV422P9,
+        Self::YUV444P9,
+        Self::YUV420P10,
+        Self::YUV422P10,
+        Self::YUV444P10,
+        Self::YUV420P12,
+        Self::YUV422P12,
+        Self::YUV444P12,
+        Self::YUV420P14,
+        Self::YUV422P14,
+        Self::YUV444P14,
+        Self::YUV420P16,
+        Self::YUV422P16,
+        Self::YUV444P16,
+        Self::YUV444PH,
+        Self::YUV444PS,
+        Self::RGB24,
+        Self::RGB27,
+        Self::RGB30,
+        Self::RGB36,
+        Self::RGB42,
+        Self::RGB48,
+        Self::RGBH,
+        Self::RGBS,
+    ];
+
+    /// The name of the preset, the same as the variant name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Gray8 => "Gray8",
+            Self::Gray9 => "Gray9",
+            Self::Gray10 => "Gray10",
+            Self::Gray12 => "Gray12",
+            Self::Gray14 => "Gray14",
+            Self::Gray16 => "Gray16",
+            Self::Gray32 => "Gray32",
+            Self::GrayH => "GrayH",
+            Self::GrayS => "GrayS",
+            Self::YUV410P8 => "YUV410P8",
+            Self::YUV411P8 => "YUV411P8",
+            Self::YUV440P8 => "YUV440P8",
+            Self::YUV420P8 => "YUV420P8",
+            Self::YUV422P8 => "YUV422P8",
+            Self::YUV444P8 => "YUV444P8",
+            Self::YUV420P9 => "YUV420P9",
+            Self::YUV422P9 => "YUV422P9",
+            Self::YUV444P9 => "YUV444P9",
+            Self::YUV420P10 => "YUV420P10",
+            Self::YUV422P10 => "YUV422P10",
+            Self::YUV444P10 => "YUV444P10",
+            Self::YUV420P12 => "YUV420P12",
+            Self::YUV422P12 => "YUV422P12",
+            Self::YUV444P12 => "YUV444P12",
+            Self::YUV420P14 => "YUV420P14",
+            Self::YUV422P14 => "YUV422P14",
+            Self::YUV444P14 => "YUV444P14",
+            Self::YUV420P16 => "YUV420P16",
+            Self::YUV422P16 => "YUV422P16",
+            Self::YUV444P16 => "YUV444P16",
+            Self::YUV444PH => "YUV444PH",
+            Self::YUV444PS => "YUV444PS",
+            Self::RGB24 => "RGB24",
+            Self::RGB27 => "RGB27",
+            Self::RGB30 => "RGB30",
+            Self::RGB36 => "RGB36",
+            Self::RGB42 => "RGB42",
+            Self::RGB48 => "RGB48",
+            Self::RGBH => "RGBH",
+            Self::RGBS => "RGBS",
+        }
+    }
+
     /// Consumes the `PresetVideoFormatID` and returns the corresponding `VideoFormat` from the core.
     #[must_use]
     pub fn into_format(self, core: &CoreRef) -> VideoFormat {
         core.get_video_format_by_id(self as u32).unwrap()
     }
 }
+
+impl fmt::Display for PresetVideoFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PresetVideoFormat {
+    type Err = FormatMappingError;
+
+    /// Parses the name of a preset, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|preset| preset.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| FormatMappingError::UnknownName {
+                name: s.to_owned(),
+                scheme: FormatNamingScheme::Preset,
+            })
+    }
+}
+
+impl TryFrom<VideoFormat> for PresetVideoFormat {
+    type Error = FormatMappingError;
+
+    fn try_from(format: VideoFormat) -> Result<Self, Self::Error> {
+        let id = make_video_id(
+            format.color_family,
+            format.sample_type,
+            format.bits_per_sample,
+            format.sub_sampling_w,
+            format.sub_sampling_h,
+        );
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|preset| *preset != Self::None && *preset as i32 == id)
+            .ok_or(FormatMappingError::NoMapping {
+                format,
+                scheme: FormatNamingScheme::Preset,
+            })
+    }
+}
+
+impl From<PresetVideoFormat> for VideoFormat {
+    /// The format of a preset, without asking the core. [`PresetVideoFormat::None`] gives an
+    /// undefined format.
+    fn from(preset: PresetVideoFormat) -> Self {
+        let id = preset as i32;
+        let color_family = match (id >> 28) & 0xf {
+            1 => ColorFamily::Gray,
+            2 => ColorFamily::RGB,
+            3 => ColorFamily::YUV,
+            _ => ColorFamily::Undefined,
+        };
+        let sample_type = if (id >> 24) & 0xf == SampleType::Float as i32 {
+            SampleType::Float
+        } else {
+            SampleType::Integer
+        };
+        Self::from_parts(
+            color_family,
+            sample_type,
+            (id >> 16) & 0xff,
+            (id >> 8) & 0xff,
+            id & 0xff,
+        )
+    }
+}
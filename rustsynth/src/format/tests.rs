@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use crate::format::{ColorFamily, PresetVideoFormat, SampleType};
+    use crate::format::{
+        AudioFormat, ChannelLayout, ColorFamily, ComponentVisitor, FormatMappingError,
+        FormatNamingScheme, PresetVideoFormat, Sample, SampleType, VideoFormat,
+    };
 
     #[test]
     fn test_preset_format_values() {
@@ -66,4 +69,202 @@ mod tests {
             PresetVideoFormat::YUV444P8 as i32
         );
     }
+
+    #[test]
+    fn test_format_name_mapping() {
+        assert_eq!(VideoFormat::YUV420P8.y4m_colorspace(), Ok("420jpeg"));
+        assert_eq!(VideoFormat::YUV420P8.pix_fmt(), Ok("yuv420p"));
+        assert_eq!(VideoFormat::YUV420P8.fourcc(), Ok(*b"I420"));
+        assert_eq!(VideoFormat::GRAY8.y4m_colorspace(), Ok("mono"));
+        assert_eq!(VideoFormat::RGB24.pix_fmt(), Ok("gbrp"));
+
+        let yuv422p10 = VideoFormat::from_pix_fmt("yuv422p10le").unwrap();
+        assert_eq!(yuv422p10.bits_per_sample, 10);
+        assert_eq!(yuv422p10.bytes_per_sample, 2);
+        assert_eq!((yuv422p10.sub_sampling_w, yuv422p10.sub_sampling_h), (1, 0));
+        assert_eq!(yuv422p10.y4m_colorspace(), Ok("422p10"));
+
+        assert_eq!(
+            VideoFormat::from_y4m_colorspace("420mpeg2"),
+            Ok(VideoFormat::YUV420P8)
+        );
+        assert_eq!(VideoFormat::from_fourcc(*b"Y800"), Ok(VideoFormat::GRAY8));
+        let grays = VideoFormat::from_pix_fmt("grayf32le").unwrap();
+        assert_eq!(
+            (grays.sample_type, grays.bytes_per_sample, grays.num_planes),
+            (SampleType::Float, 4, 1)
+        );
+    }
+
+    #[test]
+    fn test_format_name_mapping_errors() {
+        assert_eq!(
+            VideoFormat::RGB24.y4m_colorspace(),
+            Err(FormatMappingError::NoMapping {
+                format: VideoFormat::RGB24,
+                scheme: FormatNamingScheme::Y4m,
+            })
+        );
+        assert!(VideoFormat::from_pix_fmt("nv12").is_err());
+        assert!(matches!(
+            VideoFormat::from_y4m_colorspace("420p11"),
+            Err(FormatMappingError::UnknownName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_preset_names_round_trip() {
+        for &preset in PresetVideoFormat::ALL {
+            let name = preset.to_string();
+            assert_eq!(name.parse::<PresetVideoFormat>(), Ok(preset));
+            assert_eq!(name.to_lowercase().parse::<PresetVideoFormat>(), Ok(preset));
+            if preset != PresetVideoFormat::None {
+                let format = VideoFormat::from(preset);
+                assert_eq!(PresetVideoFormat::try_from(format), Ok(preset));
+                assert_eq!(format.to_string(), name);
+                assert_eq!(name.parse::<VideoFormat>(), Ok(format));
+            }
+        }
+        assert_eq!(
+            VideoFormat::from(PresetVideoFormat::YUV420P8),
+            VideoFormat::YUV420P8
+        );
+        assert!("YUV420P7".parse::<PresetVideoFormat>().is_err());
+    }
+
+    #[test]
+    fn test_video_format_descriptions() {
+        let format: VideoFormat = "yuv422p12".parse().unwrap();
+        assert_eq!(
+            PresetVideoFormat::try_from(format),
+            Ok(PresetVideoFormat::YUV422P12)
+        );
+
+        let format: VideoFormat = "rgbs".parse().unwrap();
+        assert_eq!(
+            (
+                format.color_family,
+                format.sample_type,
+                format.bits_per_sample
+            ),
+            (ColorFamily::RGB, SampleType::Float, 32)
+        );
+        assert_eq!("rgbp8".parse(), Ok(VideoFormat::RGB24));
+
+        let odd: VideoFormat = "yuv420p11".parse().unwrap();
+        assert_eq!(odd.bytes_per_sample, 2);
+        assert_eq!(
+            PresetVideoFormat::try_from(odd),
+            Err(FormatMappingError::NoMapping {
+                format: odd,
+                scheme: FormatNamingScheme::Preset,
+            })
+        );
+        assert_eq!(odd.to_string(), "yuv420p11");
+        assert_eq!(odd.to_string().parse(), Ok(odd));
+
+        for invalid in ["yuv423p8", "yuv420p", "gray4", "cmyk8", ""] {
+            assert!(invalid.parse::<VideoFormat>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_audio_format_descriptions() {
+        assert_eq!("s16".parse(), Ok(AudioFormat::STEREO16));
+        assert_eq!("s16:mono".parse(), Ok(AudioFormat::MONO16));
+
+        let format: AudioFormat = "f32:5.1".parse().unwrap();
+        assert_eq!(format.sample_type, SampleType::Float);
+        assert_eq!(format.bytes_per_sample, 4);
+        assert_eq!(format.num_channels, 6);
+        assert_eq!(format.to_string(), "f32:5.1");
+
+        let custom = AudioFormat::from_parts(
+            SampleType::Integer,
+            24,
+            ChannelLayout::FRONT_LEFT | ChannelLayout::LOW_FREQUENCY,
+        );
+        assert_eq!(custom.to_string(), "s24:FL+LFE");
+        assert_eq!(custom.to_string().parse(), Ok(custom));
+
+        assert_eq!("s24:0x9".parse(), Ok(custom));
+
+        let silent = AudioFormat::from_parts(SampleType::Float, 32, ChannelLayout::empty());
+        assert_eq!(silent.num_channels, 0);
+        assert_eq!(silent.to_string(), "f32:0x0");
+        assert_eq!(silent.to_string().parse(), Ok(silent));
+
+        for invalid in ["u16", "s16:quadro", "s", "f32:0x"] {
+            assert!(invalid.parse::<AudioFormat>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_channel_layout_names() {
+        assert_eq!("5.1(side)".parse(), Ok(ChannelLayout::SURROUND_5_1_SIDE));
+        assert_eq!("STEREO".parse(), Ok(ChannelLayout::STEREO));
+        assert_eq!("FL+FR+LFE".parse(), Ok(ChannelLayout::SURROUND_2_1));
+        assert_eq!("0x3".parse(), Ok(ChannelLayout::STEREO));
+        assert_eq!(ChannelLayout::empty().to_string(), "0x0");
+        assert_eq!("0x0".parse(), Ok(ChannelLayout::empty()));
+        assert_eq!(ChannelLayout::SURROUND_7_1.to_string(), "7.1");
+        assert_eq!(ChannelLayout::SURROUND_4_0.to_string(), "quad");
+        assert_eq!(
+            ChannelLayout::SURROUND_4_1.to_string().parse(),
+            Ok(ChannelLayout::SURROUND_4_1)
+        );
+        assert_eq!(ChannelLayout::FRONT_CENTER.ffmpeg_name(), Some("mono"));
+        assert_eq!(ChannelLayout::MONO.ffmpeg_name(), Some("mono"));
+        assert_eq!(ChannelLayout::SURROUND_4_1.ffmpeg_name(), None);
+        assert!("FL+XX".parse::<ChannelLayout>().is_err());
+    }
+
+    #[test]
+    fn test_wave_channel_mask() {
+        assert_eq!(ChannelLayout::SURROUND_5_1.wave_channel_mask(), Ok(0x3f));
+        assert_eq!(ChannelLayout::SURROUND_7_1.wave_channel_mask(), Ok(0x63f));
+        assert_eq!(
+            ChannelLayout::from_wave_channel_mask(0x60f),
+            Ok(ChannelLayout::SURROUND_5_1_SIDE)
+        );
+        assert_eq!(
+            ChannelLayout::DOWNMIX.wave_channel_mask(),
+            Err(FormatMappingError::NoChannelMapping {
+                layout: ChannelLayout::DOWNMIX,
+                scheme: FormatNamingScheme::WaveChannelMask,
+            })
+        );
+        assert!(ChannelLayout::from_wave_channel_mask(0x8000_0000).is_err());
+        assert!(ChannelLayout::from_wave_channel_mask(0x40000).is_err());
+    }
+
+    struct SampleInfo;
+
+    impl ComponentVisitor for SampleInfo {
+        type Output = (usize, f64);
+
+        fn visit<T: Sample>(self, bits_per_sample: i32) -> Self::Output {
+            (std::mem::size_of::<T>(), T::peak(bits_per_sample).into())
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let dispatch = |name: &str| name.parse::<VideoFormat>().unwrap().dispatch(SampleInfo);
+        assert_eq!(dispatch("yuv420p8"), Ok((1, 255.0)));
+        assert_eq!(dispatch("yuv420p10"), Ok((2, 1023.0)));
+        assert_eq!(dispatch("gray16"), Ok((2, 65535.0)));
+        assert_eq!(dispatch("gray32"), Ok((4, f64::from(u32::MAX))));
+        assert_eq!(dispatch("rgbs"), Ok((4, 1.0)));
+        #[cfg(feature = "f16-pixel-type")]
+        assert_eq!(dispatch("grayh"), Ok((2, 1.0)));
+        #[cfg(not(feature = "f16-pixel-type"))]
+        assert_eq!(
+            dispatch("grayh"),
+            Err(crate::format::FormatError::UnsupportedSample {
+                sample_type: SampleType::Float,
+                bytes_per_sample: 2,
+            })
+        );
+    }
 }
@@ -1,6 +1,11 @@
+use std::{fmt, str::FromStr};
+
 use crate::{
     api::API,
-    format::{ColorFamily, FormatError, SampleType},
+    format::{
+        ColorFamily, FormatError, FormatMappingError, FormatNamingScheme, PresetVideoFormat,
+        SampleType,
+    },
 };
 use rustsynth_sys as ffi;
 
@@ -35,6 +40,38 @@ impl VideoFormat {
         }
     }
 
+    /// Creates a `VideoFormat` from its defining fields, filling in the derived ones without asking
+    /// the core. The result is not validated.
+    #[must_use]
+    pub const fn from_parts(
+        color_family: ColorFamily,
+        sample_type: SampleType,
+        bits_per_sample: i32,
+        sub_sampling_w: i32,
+        sub_sampling_h: i32,
+    ) -> Self {
+        let bytes_per_sample = match bits_per_sample {
+            i32::MIN..=0 => 0,
+            1..=8 => 1,
+            9..=16 => 2,
+            _ => 4,
+        };
+        let num_planes = match color_family {
+            ColorFamily::Undefined => 0,
+            ColorFamily::Gray => 1,
+            ColorFamily::RGB | ColorFamily::YUV => 3,
+        };
+        Self {
+            color_family,
+            sample_type,
+            bits_per_sample,
+            bytes_per_sample,
+            sub_sampling_w,
+            sub_sampling_h,
+            num_planes,
+        }
+    }
+
     /// Creates a `VideoFormat` using `VapourSynth`'s validation.
     /// This ensures all derived fields are correctly calculated.
     pub fn query(
@@ -118,8 +155,8 @@ impl VideoFormat {
         sample_type: SampleType::Integer,
         bits_per_sample: 8,
         bytes_per_sample: 1,
-        sub_sampling_w: 2,
-        sub_sampling_h: 2,
+        sub_sampling_w: 1,
+        sub_sampling_h: 1,
         num_planes: 3,
     };
 
@@ -128,8 +165,8 @@ impl VideoFormat {
         sample_type: SampleType::Integer,
         bits_per_sample: 8,
         bytes_per_sample: 1,
-        sub_sampling_w: 1,
-        sub_sampling_h: 1,
+        sub_sampling_w: 0,
+        sub_sampling_h: 0,
         num_planes: 3,
     };
 
@@ -246,6 +283,120 @@ impl VideoFormatBuilder {
     }
 }
 
+impl FromStr for VideoFormatBuilder {
+    type Err = FormatMappingError;
+
+    /// Parses the same names as [`VideoFormat`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format: VideoFormat = s.parse()?;
+        Ok(Self::new(
+            format.color_family,
+            format.sample_type,
+            format.bits_per_sample,
+        )
+        .sub_sampling(format.sub_sampling_w, format.sub_sampling_h))
+    }
+}
+
+/// The subsampling suffixes of YUV format descriptions.
+const YUV_SUB_SAMPLING: &[(&str, i32, i32)] = &[
+    ("420", 1, 1),
+    ("422", 1, 0),
+    ("444", 0, 0),
+    ("410", 2, 2),
+    ("411", 2, 0),
+    ("440", 0, 1),
+];
+
+/// Parses a description such as `yuv422p12`, `grays` or `rgbp10`.
+fn parse_description(s: &str) -> Option<VideoFormat> {
+    let s = s.to_ascii_lowercase();
+    let (color_family, sub_sampling_w, sub_sampling_h, depth) =
+        if let Some(depth) = s.strip_prefix("gray") {
+            (ColorFamily::Gray, 0, 0, depth)
+        } else if let Some(depth) = s.strip_prefix("rgbp") {
+            (ColorFamily::RGB, 0, 0, depth)
+        } else {
+            let rest = s.strip_prefix("yuv")?;
+            let &(name, w, h) = YUV_SUB_SAMPLING
+                .iter()
+                .find(|(name, _, _)| rest.starts_with(name))?;
+            (
+                ColorFamily::YUV,
+                w,
+                h,
+                rest[name.len()..].strip_prefix('p')?,
+            )
+        };
+    let (sample_type, bits_per_sample) = match depth {
+        "h" => (SampleType::Float, 16),
+        "s" => (SampleType::Float, 32),
+        bits => (
+            SampleType::Integer,
+            bits.parse().ok().filter(|bits| (8..=32).contains(bits))?,
+        ),
+    };
+    Some(VideoFormat::from_parts(
+        color_family,
+        sample_type,
+        bits_per_sample,
+        sub_sampling_w,
+        sub_sampling_h,
+    ))
+}
+
+impl fmt::Display for VideoFormat {
+    /// Writes the preset name if there is one, otherwise a description such as `yuv420p11` that
+    /// parses back to the same format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Ok(preset) = PresetVideoFormat::try_from(*self) {
+            return f.write_str(preset.as_str());
+        }
+        match self.color_family {
+            ColorFamily::Undefined => return f.write_str("Undefined"),
+            ColorFamily::Gray => f.write_str("gray")?,
+            ColorFamily::RGB => f.write_str("rgbp")?,
+            ColorFamily::YUV => match YUV_SUB_SAMPLING
+                .iter()
+                .find(|(_, w, h)| (*w, *h) == (self.sub_sampling_w, self.sub_sampling_h))
+            {
+                Some((name, _, _)) => write!(f, "yuv{name}p")?,
+                None => write!(
+                    f,
+                    "yuv(ssw={},ssh={})p",
+                    self.sub_sampling_w, self.sub_sampling_h
+                )?,
+            },
+        }
+        match (self.sample_type, self.bits_per_sample) {
+            (SampleType::Float, 16) => f.write_str("h"),
+            (SampleType::Float, 32) => f.write_str("s"),
+            (_, bits) => write!(f, "{bits}"),
+        }
+    }
+}
+
+impl FromStr for VideoFormat {
+    type Err = FormatMappingError;
+
+    /// Parses a preset name such as `YUV420P10` or a description such as `yuv422p12`, `grays`
+    /// or `rgbp10`, ignoring case.
+    ///
+    /// Descriptions count the bits of a single sample, so `rgbp8` is the same as `RGB24`. The
+    /// result is not validated by the core, use [`VideoFormatBuilder`] for that.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(preset) = s.parse::<PresetVideoFormat>() {
+            if preset != PresetVideoFormat::None {
+                return Ok(preset.into());
+            }
+        }
+        parse_description(s).ok_or_else(|| FormatMappingError::UnknownName {
+            name: s.to_owned(),
+            scheme: FormatNamingScheme::VideoDescription,
+        })
+    }
+}
+
 /// Information about a video clip
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub struct VideoInfo {
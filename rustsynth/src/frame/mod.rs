@@ -8,13 +8,13 @@ use rustsynth_sys as ffi;
 use crate::{
     api::API,
     core::CoreRef,
-    format::{AudioFormat, MediaType, VideoFormat},
+    format::{AudioFormat, Component, MediaType, VideoFormat},
     map::{MapRef, MapResult},
 };
 
 // One frame of a clip.
 // This type is intended to be publicly used only in reference form.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Frame<'core> {
     // The actual mutability of this depends on whether it's accessed via `&Frame` or `&mut Frame`.
     handle: NonNull<ffi::VSFrame>,
@@ -658,6 +658,45 @@ impl<'core> Frame<'core> {
         }
     }
 
+    /// The rows of `plane` as samples of type `T`, trimmed to the width of the plane.
+    ///
+    /// Returns `None` if `T` doesn't match the format of the frame.
+    pub fn rows<'a, T: Component + 'a>(
+        &'a self,
+        plane: i32,
+    ) -> Option<impl Iterator<Item = &'a [T]> + 'a> {
+        if !T::is_valid(self.get_video_format()?) {
+            return None;
+        }
+        let width = self.get_width(plane) as usize;
+        let height = self.get_height(plane) as usize;
+        let stride = self.get_stride(plane) as usize / std::mem::size_of::<T>();
+        let ptr = self.get_read_ptr(plane).cast::<T>();
+        // SAFETY: the format matches `T` and planes are aligned for any sample type
+        let data = unsafe { std::slice::from_raw_parts(ptr, stride * height) };
+        Some(data.chunks(stride).map(move |row| &row[..width]))
+    }
+
+    /// The rows of `plane` as mutable samples of type `T`, trimmed to the width of the plane (only
+    /// for owned frames).
+    ///
+    /// Returns `None` if `T` doesn't match the format of the frame.
+    pub fn rows_mut<'a, T: Component + 'a>(
+        &'a mut self,
+        plane: i32,
+    ) -> Option<impl Iterator<Item = &'a mut [T]> + 'a> {
+        if !T::is_valid(self.get_video_format()?) {
+            return None;
+        }
+        let width = self.get_width(plane) as usize;
+        let height = self.get_height(plane) as usize;
+        let stride = self.get_stride(plane) as usize / std::mem::size_of::<T>();
+        let ptr = self.get_write_ptr(plane).cast::<T>();
+        // SAFETY: the format matches `T` and planes are aligned for any sample type
+        let data = unsafe { std::slice::from_raw_parts_mut(ptr, stride * height) };
+        Some(data.chunks_mut(stride).map(move |row| &mut row[..width]))
+    }
+
     #[must_use]
     pub fn planes(&self) -> Planes<'_> {
         Planes {
@@ -1,14 +1,32 @@
 //! `VapourSynth` callable functions.
 
 use rustsynth_sys as ffi;
+use std::any::Any;
+use std::convert::Infallible;
+use std::fmt::Display;
 use std::marker::PhantomData;
 use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr::NonNull;
-use std::{mem, panic, process};
+use thiserror::Error;
 
 use crate::api::API;
 use crate::core::CoreRef;
-use crate::map::MapRef;
+use crate::frame::Frame;
+use crate::map::{Map, MapError, MapRef, MapResult};
+use crate::node::Node;
+
+#[cfg(test)]
+mod tests;
+
+/// The error type for [`Function::try_call()`].
+#[derive(Error, Debug)]
+pub enum FunctionError {
+    #[error("Function call failed: {0}")]
+    CallFailed(String),
+    #[error("Map error: {0}")]
+    Map(#[from] MapError),
+}
 
 /// Holds a reference to a function that may be called.
 #[derive(Debug, PartialEq, Eq)]
@@ -62,34 +80,68 @@ impl<'core> Function<'core> {
 
     /// Creates a new function.
     ///
-    /// To indicate an error from the callback, set an error on the output map.
+    /// To indicate an error from the callback, set an error on the output map. A panic in the
+    /// callback is caught and set as the error.
     pub fn new<F>(core: CoreRef<'core>, callback: F) -> Self
     where
         F: Fn(CoreRef<'core>, &MapRef<'core>, &mut MapRef<'core>) + Send + Sync + 'core,
     {
-        unsafe extern "C" fn c_callback<'core, F>(
+        Self::try_new(core, move |core, in_, out| {
+            callback(core, in_, out);
+            Ok::<(), Infallible>(())
+        })
+    }
+
+    /// Creates a new function from a fallible callback.
+    ///
+    /// An `Err` returned by the callback, or a panic inside it, is set as the error of the output
+    /// map.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rustsynth::{core::CoreRef, function::Function};
+    /// # fn f(core: CoreRef<'_>) {
+    /// let double = Function::try_new(core, |_core, in_, out| {
+    ///     let x = in_.get_int("x")?;
+    ///     out.set_int("val", x * 2)
+    /// });
+    /// # }
+    /// ```
+    pub fn try_new<F, E>(core: CoreRef<'core>, callback: F) -> Self
+    where
+        F: Fn(CoreRef<'core>, &MapRef<'core>, &mut MapRef<'core>) -> Result<(), E>
+            + Send
+            + Sync
+            + 'core,
+        E: Display,
+    {
+        unsafe extern "C" fn c_callback<'core, F, E>(
             in_: *const ffi::VSMap,
             out: *mut ffi::VSMap,
             user_data: *mut c_void,
             core: *mut ffi::VSCore,
             _vsapi: *const ffi::VSAPI,
         ) where
-            F: Fn(CoreRef<'core>, &MapRef<'core>, &mut MapRef<'core>) + Send + Sync + 'core,
+            F: Fn(CoreRef<'core>, &MapRef<'core>, &mut MapRef<'core>) -> Result<(), E>
+                + Send
+                + Sync
+                + 'core,
+            E: Display,
         {
-            let closure = move || {
-                let core = CoreRef::from_ptr(core);
-                let in_map = MapRef::from_ptr(in_);
-                let out_map = MapRef::from_ptr_mut(out);
-                let callback = Box::from_raw(user_data.cast::<F>());
-
-                callback(core, in_map, out_map);
+            let core = CoreRef::from_ptr(core);
+            let in_map = MapRef::from_ptr(in_);
+            let out_map = MapRef::from_ptr_mut(out);
+            // Only borrowed, the callback is dropped by `c_free`
+            let callback = &*user_data.cast::<F>();
 
-                mem::forget(callback);
+            let error = match panic::catch_unwind(AssertUnwindSafe(|| {
+                callback(core, in_map, &mut *out_map)
+            })) {
+                Ok(Ok(())) => return,
+                Ok(Err(error)) => error.to_string(),
+                Err(payload) => format!("Function panicked: {}", panic_message(&*payload)),
             };
-
-            if panic::catch_unwind(closure).is_err() {
-                process::abort();
-            }
+            let _ = out_map.set_error(&error);
         }
 
         unsafe extern "C" fn c_free<F>(user_data: *mut c_void) {
@@ -100,7 +152,7 @@ impl<'core> Function<'core> {
 
         let handle = unsafe {
             API::get_cached().create_func(
-                Some(c_callback::<'core, F>),
+                Some(c_callback::<'core, F, E>),
                 Box::into_raw(data).cast(),
                 Some(c_free::<F>),
                 core.as_ptr(),
@@ -113,6 +165,39 @@ impl<'core> Function<'core> {
         }
     }
 
+    /// Creates a function taking a single argument of type `A` and returning `R` as `val`.
+    ///
+    /// The argument is read from the only key of the input map, so it can be passed under any name.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rustsynth::{core::CoreRef, function::{Function, FunctionError}, map::Map};
+    /// # fn f(core: CoreRef<'_>) -> Result<(), FunctionError> {
+    /// let double = Function::from_fn1::<i64, i64>(core, |x| x * 2);
+    /// let mut args = Map::new()?;
+    /// args.set_int("x", 21)?;
+    /// assert_eq!(double.try_call(&args)?.get_int("val")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_fn1<A, R>(core: CoreRef<'core>, f: impl Fn(A) -> R + Send + Sync + 'core) -> Self
+    where
+        A: FunctionValue<'core>,
+        R: FunctionValue<'core>,
+    {
+        Self::try_new(core, move |_core, in_, out| {
+            if in_.key_count() != 1 {
+                return Err(format!(
+                    "expected exactly one argument, got {}",
+                    in_.key_count()
+                ));
+            }
+            let key = in_.key(0).map_err(|e| e.to_string())?;
+            let arg = A::get_from_map(in_, key).map_err(|e| e.to_string())?;
+            R::store_in_map(out, "val", &f(arg)).map_err(|e| e.to_string())
+        })
+    }
+
     /// Calls the function. If the call fails `out` will have an error set.
     #[inline]
     pub fn call(&self, in_: &MapRef<'core>, out: &mut MapRef<'core>) {
@@ -124,4 +209,62 @@ impl<'core> Function<'core> {
             )
         };
     }
+
+    /// Calls the function with `args` and returns its output, or the error it set.
+    pub fn try_call(&self, args: &MapRef<'core>) -> Result<Map<'core>, FunctionError> {
+        let mut out = Map::new()?;
+        self.call(args, &mut out);
+        if let Some(error) = out.error() {
+            return Err(FunctionError::CallFailed(error.into_owned()));
+        }
+        Ok(out)
+    }
+}
+
+/// Types that can be passed to and returned from [`Function::from_fn1()`], and moved in and out
+/// of script environments with `Environment::set_var()`.
+pub trait FunctionValue<'core>: Sized {
+    /// Retrieves the value from the map.
+    fn get_from_map(map: &MapRef<'core>, key: &str) -> MapResult<Self>;
+
+    /// Sets the value in the map.
+    fn store_in_map(map: &mut MapRef<'core>, key: &str, x: &Self) -> MapResult<()>;
+}
+
+macro_rules! impl_function_value {
+    ($($ty:ty),*) => {
+        $(
+            impl<'core> FunctionValue<'core> for $ty {
+                #[inline]
+                fn get_from_map(map: &MapRef<'core>, key: &str) -> MapResult<Self> {
+                    map.get(key)
+                }
+
+                #[inline]
+                fn store_in_map(map: &mut MapRef<'core>, key: &str, x: &Self) -> MapResult<()> {
+                    map.set(key, x)
+                }
+            }
+        )*
+    };
+}
+
+impl_function_value!(
+    i64,
+    f64,
+    String,
+    Vec<i64>,
+    Vec<f64>,
+    Node<'core>,
+    Frame<'core>,
+    Function<'core>
+);
+
+/// Extracts the message of a caught panic.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
 }
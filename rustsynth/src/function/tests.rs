@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use crate::api::API;
+    use crate::core::CoreBuilder;
+    use crate::function::{panic_message, Function, FunctionError};
+    use crate::map::Map;
+
+    fn setup_api() -> API {
+        API::get().expect("Failed to get VapourSynth API")
+    }
+
+    #[test]
+    fn test_try_call_success() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let double = Function::try_new(core, |_core, in_, out| {
+            let x = in_.get_int("x")?;
+            out.set_int("val", x * 2)
+        });
+
+        let mut args = Map::new().unwrap();
+        args.set_int("x", 21).unwrap();
+        let out = double.try_call(&args).unwrap();
+        assert_eq!(out.get_int("val").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_try_call_callback_error() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let func = Function::try_new(core, |_, _, _| Err("no answer"));
+
+        let result = func.try_call(&Map::new().unwrap());
+        assert!(matches!(result, Err(FunctionError::CallFailed(e)) if e == "no answer"));
+    }
+
+    #[test]
+    fn test_try_call_str_panic() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let func = Function::new(core, |_, _, _| panic!("no answer"));
+
+        let result = func.try_call(&Map::new().unwrap());
+        assert!(matches!(
+            result,
+            Err(FunctionError::CallFailed(e)) if e == "Function panicked: no answer"
+        ));
+    }
+
+    #[test]
+    fn test_try_call_string_panic() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let answer = 42;
+        let func = Function::new(core, move |_, _, _| panic!("no answer but {answer}"));
+
+        let result = func.try_call(&Map::new().unwrap());
+        assert!(matches!(
+            result,
+            Err(FunctionError::CallFailed(e)) if e == "Function panicked: no answer but 42"
+        ));
+    }
+
+    #[test]
+    fn test_from_fn1() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let double = Function::from_fn1::<i64, i64>(core, |x| x * 2);
+
+        // The argument may be passed under any name
+        let mut args = Map::new().unwrap();
+        args.set_int("anything", 21).unwrap();
+        assert_eq!(double.try_call(&args).unwrap().get_int("val").unwrap(), 42);
+
+        let result = double.try_call(&Map::new().unwrap());
+        assert!(matches!(
+            result,
+            Err(FunctionError::CallFailed(e)) if e == "expected exactly one argument, got 0"
+        ));
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload = panic::catch_unwind(|| panic!("no answer")).unwrap_err();
+        assert_eq!(panic_message(&*payload), "no answer");
+
+        let payload = panic::catch_unwind(|| panic!("no answer but {}", 42)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "no answer but 42");
+
+        let payload = panic::catch_unwind(|| panic::panic_any(42)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "unknown panic");
+    }
+}
@@ -6,6 +6,9 @@
 #![feature(doc_cfg)]
 pub extern crate rustsynth_sys;
 pub use rustsynth_sys as ffi;
+// Lets code generated for `::rustsynth` paths compile in the crate's own tests
+#[cfg(test)]
+extern crate self as rustsynth;
 
 #[cfg(feature = "proc-macro")]
 extern crate rustsynth_derive;
@@ -14,6 +17,10 @@ extern crate rustsynth_derive;
 pub use rustsynth_derive::*;
 
 mod api;
+pub mod builtins;
+#[cfg(feature = "codegen")]
+#[doc(cfg(feature = "codegen"))]
+pub mod codegen;
 pub mod core;
 pub mod filter;
 pub mod format;
@@ -23,6 +30,7 @@ pub mod log;
 pub mod map;
 pub mod node;
 pub mod plugin;
+pub mod signature;
 #[cfg(feature = "vsscript-functions")]
 #[doc(cfg(feature = "vsscript-functions"))]
 pub mod vsscript;
@@ -1,11 +1,13 @@
 //! Module for logging functionality and integration with the `log` crate.
-use log::{debug, error, info, warn};
+use log::{debug, error, info, log, warn, Level};
 use rustsynth_sys as ffi;
 use std::{
     ffi::{c_char, c_void, CStr},
     ptr::NonNull,
 };
 
+use crate::frame::Frame;
+
 #[derive(Debug, Clone, Copy)]
 pub enum MessageType {
     Debug = 0,
@@ -98,3 +100,14 @@ impl LogHandler for LogRS {
         }
     }
 }
+
+/// Logs every property of `frame` through [`log`](https://github.com/rust-lang/log) at `level`,
+/// one `key: value` line per property.
+pub fn log_frame_props(frame: &Frame, level: Level) {
+    if !log::log_enabled!(level) {
+        return;
+    }
+    for (key, values) in frame.properties().iter() {
+        log!(level, "{key}: {values}");
+    }
+}
@@ -1,7 +1,7 @@
 use rustsynth_sys as ffi;
 use std::ops::Deref;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum DataType {
     String = 1,
     Binary = 0,
@@ -20,6 +20,7 @@ impl DataType {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Data<'elem> {
     inner: &'elem [u8],
 }
@@ -27,7 +28,7 @@ pub struct Data<'elem> {
 impl Deref for Data<'_> {
     type Target = [u8];
     fn deref(&self) -> &Self::Target {
-        self.inner
+        self.as_slice()
     }
 }
 
@@ -35,6 +36,11 @@ impl<'elem> Data<'elem> {
     pub(crate) const fn from_slice(slice: &'elem [u8]) -> Self {
         Self { inner: slice }
     }
+
+    /// Returns the underlying slice with the lifetime of the map it came from.
+    pub(crate) const fn as_slice(&self) -> &'elem [u8] {
+        self.inner
+    }
 }
 
 impl<'elem> From<&'elem [u8]> for Data<'elem> {
@@ -0,0 +1,449 @@
+//! Deserializing Rust values from `VapourSynth` maps.
+
+use rustsynth_sys as ffi;
+use serde::de::{self, Error as _, IntoDeserializer, Visitor};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+
+use super::ser::{FRAME_TOKEN, FUNCTION_TOKEN, NODE_TOKEN};
+use super::{DataType, MapError, MapRef, MapResult, ValueType};
+use crate::frame::Frame;
+use crate::function::Function;
+use crate::node::Node;
+
+/// Deserializes a value from a map.
+///
+/// Every key of the map is presented as a field. Keys holding a single element deserialize as that
+/// element, arrays deserialize as sequences, and types that ask for a sequence (like `Vec<T>`)
+/// always get one. Data with the UTF-8 hint is presented as a string, binary data as bytes, and
+/// data without a hint as a string if it is valid UTF-8. [`Node`], [`Frame`] and [`Function`]
+/// fields are taken from the map as-is.
+///
+/// Missing keys are reported as missing fields, except for `Option` fields which become `None`.
+///
+/// # Example
+/// ```no_run
+/// use rustsynth::map::{from_map, Map};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Args {
+///     radius: i64,
+///     #[serde(default)]
+///     planes: Vec<i64>,
+///     mode: Option<String>,
+/// }
+///
+/// let mut map = Map::new().unwrap();
+/// map.set_int("radius", 3).unwrap();
+/// let args: Args = from_map(&map).unwrap();
+/// assert_eq!(args.radius, 3);
+/// ```
+#[doc(cfg(feature = "serde"))]
+pub fn from_map<'de, T: Deserialize<'de>>(map: &'de MapRef<'_>) -> MapResult<T> {
+    map.deserialize_into()
+}
+
+impl MapRef<'_> {
+    /// Deserializes a value from this map.
+    ///
+    /// See [`from_map`] for how values are represented.
+    #[doc(cfg(feature = "serde"))]
+    pub fn deserialize_into<'de, T: Deserialize<'de>>(&'de self) -> MapResult<T> {
+        T::deserialize(MapDeserializer { map: self })
+    }
+}
+
+impl de::Error for MapError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// A reference handed from the map deserializer to the `Deserialize` impls of the passthrough
+/// types. It never outlives a single `deserialize_newtype_struct` call.
+enum Passthrough {
+    Node(*mut ffi::VSNode),
+    Frame(*const ffi::VSFrame),
+    Function(*mut ffi::VSFunction),
+}
+
+impl Passthrough {
+    /// Releases the reference if no visitor claimed it.
+    unsafe fn free(self) {
+        match self {
+            Self::Node(ptr) => drop(Node::from_ptr(ptr)),
+            Self::Frame(ptr) => drop(Frame::from_ptr(ptr)),
+            Self::Function(ptr) => drop(Function::from_ptr(ptr)),
+        }
+    }
+}
+
+thread_local! {
+    static PASSTHROUGH: Cell<Option<Passthrough>> = const { Cell::new(None) };
+}
+
+fn passthrough_error(what: &str) -> MapError {
+    MapError::custom(format!("{what} values can only be deserialized from a map"))
+}
+
+struct PassthroughVisitor<T>(PhantomData<T>);
+
+macro_rules! impl_passthrough_deserialize {
+    ($ty:ident, $token:ident, $variant:ident) => {
+        impl<'de> Visitor<'de> for PassthroughVisitor<$ty<'_>> {
+            type Value = Self;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(concat!("a ", stringify!($ty)))
+            }
+
+            fn visit_newtype_struct<D: de::Deserializer<'de>>(
+                self,
+                _: D,
+            ) -> Result<Self::Value, D::Error> {
+                Ok(self)
+            }
+        }
+
+        // The reference is taken from the map being deserialized, so it can't outlive the
+        // borrow of that map.
+        impl<'de: 'core, 'core> Deserialize<'de> for $ty<'core> {
+            fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_newtype_struct(
+                    $token,
+                    PassthroughVisitor::<$ty<'core>>(PhantomData),
+                )?;
+                match PASSTHROUGH.take() {
+                    // SAFETY: the map deserializer stored a reference it obtained from the map.
+                    Some(Passthrough::$variant(ptr)) => Ok(unsafe { $ty::from_ptr(ptr) }),
+                    Some(other) => {
+                        unsafe { other.free() };
+                        Err(D::Error::custom(concat!("expected a ", stringify!($ty))))
+                    }
+                    None => Err(D::Error::custom(passthrough_error(stringify!($ty)))),
+                }
+            }
+        }
+    };
+}
+
+impl_passthrough_deserialize!(Node, NODE_TOKEN, Node);
+impl_passthrough_deserialize!(Frame, FRAME_TOKEN, Frame);
+impl_passthrough_deserialize!(Function, FUNCTION_TOKEN, Function);
+
+/// Deserializer for the map itself, presenting its keys as fields.
+struct MapDeserializer<'de, 'elem> {
+    map: &'de MapRef<'elem>,
+}
+
+impl<'de> de::Deserializer<'de> for MapDeserializer<'de, '_> {
+    type Error = MapError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        visitor.visit_map(KeyAccess {
+            map: self.map,
+            index: 0,
+            key: None,
+        })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> MapResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Walks the keys of a map.
+struct KeyAccess<'de, 'elem> {
+    map: &'de MapRef<'elem>,
+    index: usize,
+    key: Option<&'de CStr>,
+}
+
+impl<'de> de::MapAccess<'de> for KeyAccess<'de, '_> {
+    type Error = MapError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> MapResult<Option<K::Value>> {
+        if self.index >= self.map.key_count() {
+            return Ok(None);
+        }
+        let key = self.map.key_raw(self.index);
+        self.index += 1;
+        self.key = Some(key);
+        let key = key
+            .to_str()
+            .map_err(|_| MapError::custom("map key is not valid UTF-8"))?;
+        seed.deserialize(de::value::BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> MapResult<V::Value> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| MapError::custom("next_value called before next_key"))?;
+        seed.deserialize(ValueDeserializer {
+            map: self.map,
+            key,
+            index: None,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.map.key_count() - self.index)
+    }
+}
+
+/// Deserializer for the values under a single key, or a single element if `index` is set.
+struct ValueDeserializer<'de, 'elem> {
+    map: &'de MapRef<'elem>,
+    key: &'de CStr,
+    index: Option<i32>,
+}
+
+impl<'de> ValueDeserializer<'de, '_> {
+    fn count(&self) -> MapResult<usize> {
+        unsafe { self.map.value_count_raw_unchecked(self.key) }
+    }
+
+    fn value_type(&self) -> MapResult<ValueType> {
+        unsafe { self.map.value_type_raw_unchecked(self.key) }
+    }
+
+    fn type_error(&self, what: &str) -> MapError {
+        MapError::custom(format!(
+            "{what} in key `{}` can't be deserialized here",
+            self.key.to_string_lossy()
+        ))
+    }
+
+    fn data(&self, index: i32) -> MapResult<&'de [u8]> {
+        unsafe { self.map.get_data_raw_unchecked(self.key, index) }.map(|data| data.as_slice())
+    }
+
+    fn visit_element<V: Visitor<'de>>(&self, index: i32, visitor: V) -> MapResult<V::Value> {
+        match self.value_type()? {
+            ValueType::Int => {
+                visitor.visit_i64(unsafe { self.map.get_int_raw_unchecked(self.key, index) }?)
+            }
+            ValueType::Float => {
+                visitor.visit_f64(unsafe { self.map.get_float_raw_unchecked(self.key, index) }?)
+            }
+            ValueType::Data => {
+                let data = self.data(index)?;
+                match self.map.data_type_hint(self.key, index) {
+                    DataType::Binary => visitor.visit_borrowed_bytes(data),
+                    DataType::String => visitor.visit_borrowed_str(
+                        std::str::from_utf8(data)
+                            .map_err(|_| MapError::custom("string data is not valid UTF-8"))?,
+                    ),
+                    DataType::Unknown => match std::str::from_utf8(data) {
+                        Ok(s) => visitor.visit_borrowed_str(s),
+                        Err(_) => visitor.visit_borrowed_bytes(data),
+                    },
+                }
+            }
+            ValueType::Node => Err(self.type_error("a node")),
+            ValueType::Frame => Err(self.type_error("a frame")),
+            ValueType::Function => Err(self.type_error("a function")),
+        }
+    }
+
+    fn visit_seq<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        let len = self.count()?;
+        visitor.visit_seq(ElementAccess {
+            map: self.map,
+            key: self.key,
+            index: 0,
+            len,
+        })
+    }
+
+    /// Takes a reference to the element from the map and hands it to a passthrough visitor.
+    fn visit_passthrough<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> MapResult<V::Value> {
+        let index = self.index.unwrap_or(0);
+        let passthrough = unsafe {
+            match name {
+                NODE_TOKEN => {
+                    let node = self.map.get_node_raw_unchecked(self.key, index)?;
+                    Passthrough::Node(std::mem::ManuallyDrop::new(node).as_ptr())
+                }
+                FRAME_TOKEN => {
+                    let frame = self.map.get_frame_raw_unchecked(self.key, index)?;
+                    Passthrough::Frame(std::mem::ManuallyDrop::new(frame).as_ptr())
+                }
+                _ => {
+                    let func = self.map.get_function_raw_unchecked(self.key, index)?;
+                    Passthrough::Function(std::mem::ManuallyDrop::new(func).as_ptr())
+                }
+            }
+        };
+        if let Some(stale) = PASSTHROUGH.replace(Some(passthrough)) {
+            unsafe { stale.free() };
+        }
+        let result = visitor.visit_newtype_struct(().into_deserializer());
+        if result.is_err() {
+            if let Some(unclaimed) = PASSTHROUGH.take() {
+                unsafe { unclaimed.free() };
+            }
+        }
+        result
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de, '_> {
+    type Error = MapError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        match self.index {
+            Some(index) => self.visit_element(index, visitor),
+            None if self.count()? == 1 => self.visit_element(0, visitor),
+            None => self.visit_seq(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        let index = self.index.unwrap_or(0);
+        match self.value_type()? {
+            ValueType::Int => {
+                visitor.visit_bool(unsafe { self.map.get_int_raw_unchecked(self.key, index) }? != 0)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        let index = self.index.unwrap_or(0);
+        match self.value_type()? {
+            ValueType::Data => visitor.visit_borrowed_bytes(self.data(index)?),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> MapResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> MapResult<V::Value> {
+        match name {
+            NODE_TOKEN | FRAME_TOKEN | FUNCTION_TOKEN => self.visit_passthrough(name, visitor),
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        match self.index {
+            Some(_) => self.deserialize_any(visitor),
+            None => self.visit_seq(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, visitor: V) -> MapResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> MapResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> MapResult<V::Value> {
+        let index = self.index.unwrap_or(0);
+        let variant = std::str::from_utf8(self.data(index)?)
+            .map_err(|_| MapError::custom("enum variant name is not valid UTF-8"))?;
+        visitor.visit_enum(variant.into_deserializer())
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> MapResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        map struct identifier
+    }
+}
+
+/// Walks the elements under a single key.
+struct ElementAccess<'de, 'elem> {
+    map: &'de MapRef<'elem>,
+    key: &'de CStr,
+    index: usize,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for ElementAccess<'de, '_> {
+    type Error = MapError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> MapResult<Option<T::Value>> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let index = i32::try_from(self.index).map_err(|_| MapError::IndexOutOfBounds)?;
+        self.index += 1;
+        seed.deserialize(ValueDeserializer {
+            map: self.map,
+            key: self.key,
+            index: Some(index),
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
@@ -8,6 +8,11 @@ use thiserror::Error;
 pub enum MapError {
     #[error("The requested key wasn't found in the map")]
     KeyNotFound,
+    #[error("The key `{key}` of the field `{field}` wasn't found in the map")]
+    MissingField {
+        key: &'static str,
+        field: &'static str,
+    },
     #[error("The requested index was out of bounds")]
     IndexOutOfBounds,
     #[error("The given/requested value type doesn't match the type of the property")]
@@ -22,6 +27,8 @@ pub enum MapError {
     Error,
     #[error("UTF-8 conversion error: {0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
+    #[error("{0}")]
+    Custom(String),
 }
 
 impl From<MapError> for String {
@@ -1,4 +1,6 @@
-use super::{CString, Data, Function, MapError, MapRef, MapResult, Node, PhantomData, ValueType};
+use super::{
+    CString, Data, Function, MapError, MapRef, MapResult, MapValues, Node, PhantomData, ValueType,
+};
 use crate::frame::Frame;
 
 /// An iterator over the keys of a map.
@@ -43,6 +45,52 @@ impl<'map> Iterator for Keys<'map, '_> {
 
 impl ExactSizeIterator for Keys<'_, '_> {}
 
+/// An iterator over the keys of a map along with all of their values.
+///
+/// Keys that aren't valid UTF-8 or hold values that can't be read are skipped.
+///
+/// Created by [`MapRef::iter()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Iter<'map, 'elem: 'map> {
+    map: &'map MapRef<'elem>,
+    count: usize,
+    index: usize,
+}
+
+impl<'map, 'elem> Iter<'map, 'elem> {
+    #[inline]
+    pub(crate) fn new(map: &'map MapRef<'elem>) -> Self {
+        Self {
+            map,
+            count: map.key_count(),
+            index: 0,
+        }
+    }
+}
+
+impl<'map, 'elem> Iterator for Iter<'map, 'elem> {
+    type Item = (&'map str, MapValues<'map, 'elem>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.count {
+            let raw_key = self.map.key_raw(self.index);
+            self.index += 1;
+            let Ok(key) = raw_key.to_str() else {
+                continue;
+            };
+            if let Ok(values) = unsafe { self.map.values_raw_unchecked(raw_key) } {
+                return Some((key, values));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count - self.index))
+    }
+}
+
 /// An iterator over the values associated with a certain key of a map.
 #[derive(Debug, Clone)]
 pub struct ValueIter<'map, 'elem: 'map, T: ?Sized> {
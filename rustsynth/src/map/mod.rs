@@ -3,6 +3,7 @@
 use rustsynth_sys as ffi;
 use std::borrow::Cow;
 use std::ffi::{c_int, CStr, CString};
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::ptr::NonNull;
@@ -17,21 +18,27 @@ mod errors;
 pub use errors::{InvalidKeyError, MapError, MapResult};
 
 mod iterators;
-pub use self::iterators::{Keys, ValueIter};
+pub use self::iterators::{Iter, Keys, ValueIter};
 
 mod value;
-pub use self::value::{Value, ValueNotArray, ValueType};
+pub use self::value::{MapValues, Value, ValueNotArray, ValueType};
 
 mod data;
 pub use self::data::{Data, DataType};
 
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+pub use self::{de::from_map, ser::to_map};
+
 #[cfg(test)]
 mod tests;
 
 /// An owned `VapourSynth` map that frees on drop.
 ///
 /// A map contains key-value pairs where the value is zero or more elements of a certain type.
-#[derive(Debug)]
 pub struct Map<'elem> {
     handle: NonNull<ffi::VSMap>,
     _elem: PhantomData<&'elem ()>,
@@ -44,7 +51,6 @@ unsafe impl Sync for Map<'_> {}
 ///
 /// This type is used when the map is owned by another object (like frame properties).
 /// It can only be accessed through references (`&MapRef` or `&mut MapRef`).
-#[derive(Debug)]
 #[repr(transparent)]
 pub struct MapRef<'elem> {
     inner: ffi::VSMap,
@@ -77,6 +83,42 @@ impl<'elem> DerefMut for Map<'elem> {
     }
 }
 
+impl Clone for Map<'_> {
+    fn clone(&self) -> Self {
+        let mut map = Map::new().expect("Failed to create map");
+        unsafe { API::get_cached().copy_map(&self.inner, &mut map.inner) };
+        map
+    }
+}
+
+impl fmt::Debug for Map<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for Map<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl fmt::Debug for MapRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Prints one `key: value` line per key.
+impl fmt::Display for MapRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, values) in self.iter() {
+            writeln!(f, "{key}: {values}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Turns a `map_get_something()` error into a `Result`.
 #[inline]
 fn handle_get_prop_error(error: i32) -> MapResult<()> {
@@ -213,12 +255,40 @@ impl<'elem> MapRef<'elem> {
         }
     }
 
+    /// Copies all keys of `other` into this map.
+    ///
+    /// Keys present in both maps are replaced with the values from `other` if `overwrite` is
+    /// `true`, and left untouched otherwise.
+    /// Fails if the temporary map needed when `overwrite` is `false` can't be created.
+    pub fn merge(&mut self, other: &MapRef<'elem>, overwrite: bool) -> MapResult<()> {
+        unsafe {
+            if overwrite {
+                API::get_cached().copy_map(&other.inner, &mut self.inner);
+                return Ok(());
+            }
+
+            let mut missing = Map::new()?;
+            API::get_cached().copy_map(&other.inner, &mut missing.inner);
+            for index in 0..self.key_count() {
+                let key = self.key_raw(index);
+                if missing.value_count_raw_unchecked(key).is_ok() {
+                    let _ = missing.delete_key_raw_unchecked(key);
+                }
+            }
+            API::get_cached().copy_map(&missing.inner, &mut self.inner);
+        }
+        Ok(())
+    }
+
     /// Returns the error message contained in the map, if any.
     #[inline]
-    pub fn error(&'_ self) -> Result<&str, std::str::Utf8Error> {
+    #[must_use]
+    pub fn error(&'_ self) -> Option<Cow<'_, str>> {
         let error_message = unsafe { API::get_cached().map_get_error(&self.inner) };
-        let error_message = unsafe { CStr::from_ptr(error_message) };
-        error_message.to_str()
+        if error_message.is_null() {
+            return None;
+        }
+        Some(unsafe { CStr::from_ptr(error_message) }.to_string_lossy())
     }
 
     /// Adds an error message to a map. The map is cleared first.
@@ -268,6 +338,25 @@ impl<'elem> MapRef<'elem> {
         Keys::new(self)
     }
 
+    /// Returns an iterator over all keys in a map along with their values.
+    ///
+    /// Keys that aren't valid UTF-8 or whose values can't be read are skipped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rustsynth::frame::Frame;
+    /// # fn dump(frame: &Frame) {
+    /// for (key, values) in frame.properties().iter() {
+    ///     println!("{key}: {values}");
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn iter(&'_ self) -> Iter<'_, 'elem> {
+        Iter::new(self)
+    }
+
     /// Returns the number of elements associated with a key in a map.
     ///
     /// # Safety
@@ -290,26 +379,33 @@ impl<'elem> MapRef<'elem> {
         unsafe { self.value_count_raw_unchecked(&key) }
     }
 
+    /// Retrieves the raw property type of a key, telling video and audio nodes and frames apart.
+    ///
+    /// # Safety
+    /// The caller must ensure `key` is valid.
+    #[inline]
+    pub(crate) unsafe fn property_type_raw_unchecked(&self, key: &CStr) -> c_int {
+        API::get_cached().map_get_type(&self.inner, key.as_ptr())
+    }
+
     /// Retrieves a value type from a map.
     ///
     /// # Safety
     /// The caller must ensure `key` is valid.
     #[inline]
     pub(crate) unsafe fn value_type_raw_unchecked(&self, key: &CStr) -> MapResult<ValueType> {
-        match API::get_cached().map_get_type(&self.inner, key.as_ptr()) {
+        match self.property_type_raw_unchecked(key) {
             x if x == ffi::VSPropertyType::ptUnset as c_int => Err(MapError::KeyNotFound),
             x if x == ffi::VSPropertyType::ptInt as c_int => Ok(ValueType::Int),
             x if x == ffi::VSPropertyType::ptFloat as c_int => Ok(ValueType::Float),
             x if x == ffi::VSPropertyType::ptData as c_int => Ok(ValueType::Data),
-            x if x
-                == ffi::VSPropertyType::ptVideoNode as c_int
-                    | ffi::VSPropertyType::ptAudioNode as c_int =>
+            x if x == ffi::VSPropertyType::ptVideoNode as c_int
+                || x == ffi::VSPropertyType::ptAudioNode as c_int =>
             {
                 Ok(ValueType::Node)
             }
-            x if x
-                == ffi::VSPropertyType::ptVideoFrame as c_int
-                    | ffi::VSPropertyType::ptAudioFrame as c_int =>
+            x if x == ffi::VSPropertyType::ptVideoFrame as c_int
+                || x == ffi::VSPropertyType::ptAudioFrame as c_int =>
             {
                 Ok(ValueType::Frame)
             }
@@ -325,6 +421,58 @@ impl<'elem> MapRef<'elem> {
         unsafe { self.value_type_raw_unchecked(&key) }
     }
 
+    /// Retrieves all values associated with a key, whatever their type.
+    #[inline]
+    pub fn values(&self, key: &str) -> MapResult<MapValues<'_, 'elem>> {
+        let key = MapRef::make_raw_key(key)?;
+        unsafe { self.values_raw_unchecked(&key) }
+    }
+
+    /// Retrieves all values associated with a key, whatever their type.
+    ///
+    /// # Safety
+    /// The caller must ensure `key` is valid.
+    pub(crate) unsafe fn values_raw_unchecked(
+        &self,
+        key: &CStr,
+    ) -> MapResult<MapValues<'_, 'elem>> {
+        let count = self.value_count_raw_unchecked(key)?;
+        let value_type = self.value_type_raw_unchecked(key)?;
+        let indices = 0..count as i32;
+
+        Ok(match value_type {
+            ValueType::Int if count == 0 => MapValues::Ints(&[]),
+            ValueType::Int => MapValues::Ints(self.get_int_array_raw_unchecked(key)?),
+            ValueType::Float if count == 0 => MapValues::Floats(&[]),
+            ValueType::Float => MapValues::Floats(self.get_float_array_raw_unchecked(key)?),
+            ValueType::Data => MapValues::Data(
+                indices
+                    .map(|i| {
+                        Ok((
+                            self.get_data_raw_unchecked(key, i)?,
+                            self.data_type_hint(key, i),
+                        ))
+                    })
+                    .collect::<MapResult<_>>()?,
+            ),
+            ValueType::Node => MapValues::Nodes(
+                indices
+                    .map(|i| self.get_node_raw_unchecked(key, i))
+                    .collect::<MapResult<_>>()?,
+            ),
+            ValueType::Frame => MapValues::Frames(
+                indices
+                    .map(|i| self.get_frame_raw_unchecked(key, i))
+                    .collect::<MapResult<_>>()?,
+            ),
+            ValueType::Function => MapValues::Functions(
+                indices
+                    .map(|i| self.get_function_raw_unchecked(key, i))
+                    .collect::<MapResult<_>>()?,
+            ),
+        })
+    }
+
     /// Deletes the given key.
     ///
     /// # Safety
@@ -698,6 +846,26 @@ impl<'elem> MapRef<'elem> {
         unsafe { self.append_data_raw_unchecked(&key, x) }
     }
 
+    /// Appends a string to a map, recorded with the UTF-8 type hint.
+    #[inline]
+    pub fn append_string(&mut self, key: &str, x: &str) -> MapResult<()> {
+        let key = MapRef::make_raw_key(key)?;
+        unsafe {
+            self.append_data_with_hint_raw_unchecked(
+                &key,
+                x.as_bytes(),
+                ffi::VSDataTypeHint::dtUtf8,
+            )
+        }
+    }
+
+    /// Appends binary data to a map, recorded with the binary type hint.
+    #[inline]
+    pub fn append_binary(&mut self, key: &str, x: &[u8]) -> MapResult<()> {
+        let key = MapRef::make_raw_key(key)?;
+        unsafe { self.append_data_with_hint_raw_unchecked(&key, x, ffi::VSDataTypeHint::dtBinary) }
+    }
+
     /// Appends a node to a map.
     #[inline]
     pub fn append_node(&mut self, key: &str, x: &Node) -> MapResult<()> {
@@ -764,12 +932,26 @@ impl<'elem> MapRef<'elem> {
         &mut self,
         key: &CStr,
         x: &[u8],
+    ) -> MapResult<()> {
+        self.append_data_with_hint_raw_unchecked(key, x, ffi::VSDataTypeHint::dtUnknown)
+    }
+
+    /// Appends data with the given type hint to a map.
+    ///
+    /// # Safety
+    /// The caller must ensure `key` is valid.
+    #[inline]
+    pub(crate) unsafe fn append_data_with_hint_raw_unchecked(
+        &mut self,
+        key: &CStr,
+        x: &[u8],
+        hint: ffi::VSDataTypeHint,
     ) -> MapResult<()> {
         let error = API::get_cached().map_set_data(
             &mut *self.as_mut_ptr(),
             key.as_ptr(),
             x,
-            ffi::VSDataTypeHint::dtUnknown,
+            hint,
             ffi::VSMapAppendMode::maAppend,
         );
 
@@ -846,6 +1028,8 @@ impl<'elem> MapRef<'elem> {
         Ok(())
     }
 
+    /// Sets a property value to a string, recorded with the UTF-8 type hint.
+    #[inline]
     pub fn set_string(&mut self, key: &str, x: &str) -> MapResult<()> {
         let key = MapRef::make_raw_key(key)?;
         unsafe {
@@ -854,6 +1038,16 @@ impl<'elem> MapRef<'elem> {
         Ok(())
     }
 
+    /// Sets a property value to binary data, recorded with the binary type hint.
+    #[inline]
+    pub fn set_binary(&mut self, key: &str, x: &[u8]) -> MapResult<()> {
+        let key = MapRef::make_raw_key(key)?;
+        unsafe {
+            self.set_data_with_hint_raw_unchecked(&key, x, ffi::VSDataTypeHint::dtBinary);
+        }
+        Ok(())
+    }
+
     /// Sets a property value to a floating point number.
     #[inline]
     pub fn set_float(&mut self, key: &str, x: f64) -> MapResult<()> {
@@ -982,28 +1176,34 @@ impl<'elem> MapRef<'elem> {
     /// The caller must ensure `key` is valid.
     #[inline]
     pub(crate) unsafe fn set_data_raw_unchecked(&mut self, key: &CStr, x: &[u8]) {
-        let error = API::get_cached().map_set_data(
-            &mut *self.as_mut_ptr(),
-            key.as_ptr(),
-            x,
-            ffi::VSDataTypeHint::dtUnknown,
-            ffi::VSMapAppendMode::maReplace,
-        );
-
-        debug_assert!(error == 0);
+        self.set_data_with_hint_raw_unchecked(key, x, ffi::VSDataTypeHint::dtUnknown);
     }
 
-    /// Sets a property value to data.
+    /// Sets a property value to a string.
     ///
     /// # Safety
     /// The caller must ensure `key` is valid.
     #[inline]
     pub(crate) unsafe fn set_string_raw_unchecked(&mut self, key: &CStr, x: &str) {
+        self.set_data_with_hint_raw_unchecked(key, x.as_bytes(), ffi::VSDataTypeHint::dtUtf8);
+    }
+
+    /// Sets a property value to data with the given type hint.
+    ///
+    /// # Safety
+    /// The caller must ensure `key` is valid.
+    #[inline]
+    pub(crate) unsafe fn set_data_with_hint_raw_unchecked(
+        &mut self,
+        key: &CStr,
+        x: &[u8],
+        hint: ffi::VSDataTypeHint,
+    ) {
         let error = API::get_cached().map_set_data(
             &mut *self.as_mut_ptr(),
             key.as_ptr(),
-            x.as_bytes(),
-            ffi::VSDataTypeHint::dtUtf8,
+            x,
+            hint,
             ffi::VSMapAppendMode::maReplace,
         );
 
@@ -1164,8 +1364,20 @@ impl<'elem> MapRef<'elem> {
     // TODO: Saturated retrival
 }
 
-pub trait IntoOwnedMap {
-    fn into_owned_map<'elem>(self) -> Map<'elem>;
+/// Types that can be converted into an owned map.
+///
+/// Usually derived with `#[derive(IntoOwnedMap)]` (requires the `proc-macro` feature).
+pub trait IntoOwnedMap<'elem> {
+    /// Creates a new map holding the contents of `self`.
+    fn into_owned_map(self) -> MapResult<Map<'elem>>;
+}
+
+/// Types that can be built from the keys of a map.
+///
+/// Usually derived with `#[derive(FromMap)]` (requires the `proc-macro` feature).
+pub trait FromMap<'elem>: Sized {
+    /// Reads a value out of `map`.
+    fn from_map(map: &MapRef<'elem>) -> MapResult<Self>;
 }
 
 impl Drop for Map<'_> {
@@ -0,0 +1,757 @@
+//! Serializing Rust values into `VapourSynth` maps.
+
+use rustsynth_sys as ffi;
+use serde::ser::{self, Error as _, Impossible, Serialize};
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::mem::ManuallyDrop;
+
+use super::{Map, MapError, MapRef, MapResult};
+use crate::frame::Frame;
+use crate::function::Function;
+use crate::node::Node;
+
+/// Newtype struct name used by `Node`'s `Serialize` impl to tell the map serializer to take it from
+/// [`PASSTHROUGH`].
+pub(crate) const NODE_TOKEN: &str = "$rustsynth::private::Node";
+/// Newtype struct name used by `Frame`'s `Serialize` impl to tell the map serializer to take it
+/// from [`PASSTHROUGH`].
+pub(crate) const FRAME_TOKEN: &str = "$rustsynth::private::Frame";
+/// Newtype struct name used by `Function`'s `Serialize` impl to tell the map serializer to take it
+/// from [`PASSTHROUGH`].
+pub(crate) const FUNCTION_TOKEN: &str = "$rustsynth::private::Function";
+
+/// A reference lent by the `Serialize` impls of the passthrough types to the map serializer. It is
+/// only set while the value it points to is borrowed by its `serialize` call.
+#[derive(Clone, Copy)]
+enum Passthrough {
+    Node(*mut ffi::VSNode),
+    Frame(*const ffi::VSFrame),
+    Function(*mut ffi::VSFunction),
+}
+
+thread_local! {
+    static PASSTHROUGH: Cell<Option<Passthrough>> = const { Cell::new(None) };
+}
+
+/// Restores the reference lent by an outer `serialize` call, even when unwinding.
+struct PassthroughGuard(Option<Passthrough>);
+
+impl Drop for PassthroughGuard {
+    fn drop(&mut self) {
+        PASSTHROUGH.set(self.0);
+    }
+}
+
+/// The content of a passthrough newtype, which serializers other than the map serializer see.
+struct PassthroughContent(&'static str);
+
+impl Serialize for PassthroughContent {
+    fn serialize<S: ser::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(S::Error::custom(format!(
+            "{} values can only be serialized into a map",
+            self.0
+        )))
+    }
+}
+
+/// Lends `passthrough` to the map serializer for the duration of the call.
+fn serialize_passthrough<S: ser::Serializer>(
+    serializer: S,
+    token: &'static str,
+    what: &'static str,
+    passthrough: Passthrough,
+) -> Result<S::Ok, S::Error> {
+    let _guard = PassthroughGuard(PASSTHROUGH.replace(Some(passthrough)));
+    serializer.serialize_newtype_struct(token, &PassthroughContent(what))
+}
+
+/// Serializes `value` into a new owned map.
+///
+/// The top-level value must be a struct or a map with string keys. Every field becomes a key:
+///
+/// - booleans and integers are stored as ints, floats as floats,
+/// - strings and chars are stored as data with the UTF-8 hint, bytes with the binary hint,
+/// - sequences and tuples are stored as arrays of their elements,
+/// - `None` and unit values leave the key unset,
+/// - unit enum variants are stored as their name,
+/// - [`Node`], [`Frame`] and [`Function`] values are stored as-is. Other serializers, such as
+///   JSON ones, return an error for them.
+///
+/// Nested maps, nested sequences and data-carrying enum variants are not representable and return
+/// an error. Empty sequences are not stored since `VapourSynth` arrays are typed; use
+/// `#[serde(default)]` on the receiving side.
+///
+/// # Example
+/// ```no_run
+/// use rustsynth::map::to_map;
+///
+/// #[derive(serde::Serialize)]
+/// struct Args {
+///     width: i64,
+///     height: i64,
+///     planes: Vec<i64>,
+///     kernel: String,
+/// }
+///
+/// let map = to_map(&Args { width: 1920, height: 1080, planes: vec![0, 1, 2], kernel: "bicubic".into() }).unwrap();
+/// assert_eq!(map.get_int("width").unwrap(), 1920);
+/// ```
+#[doc(cfg(feature = "serde"))]
+pub fn to_map<'elem, T: Serialize + ?Sized>(value: &T) -> MapResult<Map<'elem>> {
+    let mut map = Map::new()?;
+    map.serialize_from(value)?;
+    Ok(map)
+}
+
+impl MapRef<'_> {
+    /// Serializes `value` into this map, replacing the keys it produces.
+    ///
+    /// See [`to_map`] for how values are represented.
+    #[doc(cfg(feature = "serde"))]
+    pub fn serialize_from<T: Serialize + ?Sized>(&mut self, value: &T) -> MapResult<()> {
+        value.serialize(MapSerializer { map: self })
+    }
+}
+
+impl ser::Error for MapError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl Serialize for Node<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_passthrough(
+            serializer,
+            NODE_TOKEN,
+            "Node",
+            Passthrough::Node(self.as_ptr()),
+        )
+    }
+}
+
+impl Serialize for Frame<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_passthrough(
+            serializer,
+            FRAME_TOKEN,
+            "Frame",
+            Passthrough::Frame(self.as_ptr()),
+        )
+    }
+}
+
+impl Serialize for Function<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_passthrough(
+            serializer,
+            FUNCTION_TOKEN,
+            "Function",
+            Passthrough::Function(self.as_ptr()),
+        )
+    }
+}
+
+fn top_level_error() -> MapError {
+    MapError::custom("only structs and maps can be serialized into a map")
+}
+
+/// Serializer for the map itself, accepting structs and maps.
+struct MapSerializer<'map, 'elem> {
+    map: &'map mut MapRef<'elem>,
+}
+
+impl<'map, 'elem> MapSerializer<'map, 'elem> {
+    fn serialize_entry<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> MapResult<()> {
+        let key = MapRef::make_raw_key(key)?;
+        match unsafe { self.map.delete_key_raw_unchecked(&key) } {
+            Ok(()) | Err(MapError::KeyNotFound) => {}
+            Err(e) => return Err(e),
+        }
+        value.serialize(ValueSerializer {
+            map: &mut *self.map,
+            key: &key,
+            in_seq: false,
+        })
+    }
+}
+
+impl<'map, 'elem> ser::Serializer for MapSerializer<'map, 'elem> {
+    type Ok = ();
+    type Error = MapError;
+    type SerializeSeq = Impossible<(), MapError>;
+    type SerializeTuple = Impossible<(), MapError>;
+    type SerializeTupleStruct = Impossible<(), MapError>;
+    type SerializeTupleVariant = Impossible<(), MapError>;
+    type SerializeMap = MapEntrySerializer<'map, 'elem>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), MapError>;
+
+    fn serialize_bool(self, _: bool) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_i8(self, _: i8) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_i16(self, _: i16) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_i32(self, _: i32) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_i64(self, _: i64) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_u8(self, _: u8) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_u16(self, _: u16) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_u32(self, _: u32) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_u64(self, _: u64) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_f32(self, _: f32) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_f64(self, _: f64) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_char(self, _: char) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_str(self, _: &str) -> MapResult<()> {
+        Err(top_level_error())
+    }
+    fn serialize_bytes(self, _: &[u8]) -> MapResult<()> {
+        Err(top_level_error())
+    }
+
+    fn serialize_none(self) -> MapResult<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> MapResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> MapResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> MapResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> MapResult<()> {
+        Err(top_level_error())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> MapResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> MapResult<()> {
+        Err(top_level_error())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> MapResult<Self::SerializeSeq> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple(self, _: usize) -> MapResult<Self::SerializeTuple> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeTupleStruct> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeTupleVariant> {
+        Err(top_level_error())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> MapResult<Self::SerializeMap> {
+        Ok(MapEntrySerializer {
+            inner: self,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> MapResult<Self::SerializeStruct> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeStructVariant> {
+        Err(top_level_error())
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer<'_, '_> {
+    type Ok = ();
+    type Error = MapError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> MapResult<()> {
+        self.serialize_entry(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> MapResult<()> {
+        let key = MapRef::make_raw_key(key)?;
+        match unsafe { self.map.delete_key_raw_unchecked(&key) } {
+            Ok(()) | Err(MapError::KeyNotFound) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn end(self) -> MapResult<()> {
+        Ok(())
+    }
+}
+
+/// Serializer for maps with string keys.
+struct MapEntrySerializer<'map, 'elem> {
+    inner: MapSerializer<'map, 'elem>,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for MapEntrySerializer<'_, '_> {
+    type Ok = ();
+    type Error = MapError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> MapResult<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> MapResult<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| MapError::custom("serialize_value called before serialize_key"))?;
+        self.inner.serialize_entry(&key, value)
+    }
+
+    fn end(self) -> MapResult<()> {
+        Ok(())
+    }
+}
+
+fn key_error() -> MapError {
+    MapError::custom("map keys must be strings")
+}
+
+/// Serializer turning a map key into a `String`.
+struct KeySerializer;
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = MapError;
+    type SerializeSeq = Impossible<String, MapError>;
+    type SerializeTuple = Impossible<String, MapError>;
+    type SerializeTupleStruct = Impossible<String, MapError>;
+    type SerializeTupleVariant = Impossible<String, MapError>;
+    type SerializeMap = Impossible<String, MapError>;
+    type SerializeStruct = Impossible<String, MapError>;
+    type SerializeStructVariant = Impossible<String, MapError>;
+
+    fn serialize_str(self, v: &str) -> MapResult<String> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_char(self, v: char) -> MapResult<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> MapResult<String> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> MapResult<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _: bool) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_i8(self, _: i8) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_i16(self, _: i16) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_i32(self, _: i32) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_i64(self, _: i64) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_u8(self, _: u8) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_u16(self, _: u16) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_u32(self, _: u32) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_u64(self, _: u64) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_f32(self, _: f32) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_f64(self, _: f64) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_bytes(self, _: &[u8]) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_none(self) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_unit(self) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> MapResult<String> {
+        Err(key_error())
+    }
+    fn serialize_seq(self, _: Option<usize>) -> MapResult<Self::SerializeSeq> {
+        Err(key_error())
+    }
+    fn serialize_tuple(self, _: usize) -> MapResult<Self::SerializeTuple> {
+        Err(key_error())
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeTupleStruct> {
+        Err(key_error())
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeTupleVariant> {
+        Err(key_error())
+    }
+    fn serialize_map(self, _: Option<usize>) -> MapResult<Self::SerializeMap> {
+        Err(key_error())
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> MapResult<Self::SerializeStruct> {
+        Err(key_error())
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeStructVariant> {
+        Err(key_error())
+    }
+}
+
+/// Serializer appending values under a single key.
+struct ValueSerializer<'map, 'elem, 'key> {
+    map: &'map mut MapRef<'elem>,
+    key: &'key CStr,
+    in_seq: bool,
+}
+
+impl ValueSerializer<'_, '_, '_> {
+    fn nested_error(&self, what: &str) -> MapError {
+        MapError::custom(format!(
+            "{what} can't be stored in key `{}`",
+            self.key.to_string_lossy()
+        ))
+    }
+
+    fn append_data(self, x: &[u8], hint: ffi::VSDataTypeHint) -> MapResult<()> {
+        unsafe {
+            self.map
+                .append_data_with_hint_raw_unchecked(self.key, x, hint)
+        }
+    }
+}
+
+impl<'map, 'elem, 'key> ser::Serializer for ValueSerializer<'map, 'elem, 'key> {
+    type Ok = ();
+    type Error = MapError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Impossible<(), MapError>;
+    type SerializeMap = Impossible<(), MapError>;
+    type SerializeStruct = Impossible<(), MapError>;
+    type SerializeStructVariant = Impossible<(), MapError>;
+
+    fn serialize_bool(self, v: bool) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> MapResult<()> {
+        unsafe { self.map.append_int_raw_unchecked(self.key, v) }
+    }
+
+    fn serialize_u8(self, v: u8) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> MapResult<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> MapResult<()> {
+        let v = i64::try_from(v)
+            .map_err(|_| MapError::custom(format!("integer {v} is out of range for i64")))?;
+        self.serialize_i64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> MapResult<()> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> MapResult<()> {
+        unsafe { self.map.append_float_raw_unchecked(self.key, v) }
+    }
+
+    fn serialize_char(self, v: char) -> MapResult<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> MapResult<()> {
+        self.append_data(v.as_bytes(), ffi::VSDataTypeHint::dtUtf8)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> MapResult<()> {
+        self.append_data(v, ffi::VSDataTypeHint::dtBinary)
+    }
+
+    fn serialize_none(self) -> MapResult<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> MapResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> MapResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> MapResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> MapResult<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> MapResult<()> {
+        if !matches!(name, NODE_TOKEN | FRAME_TOKEN | FUNCTION_TOKEN) {
+            return value.serialize(self);
+        }
+        // SAFETY: the reference was lent by one of the `Serialize` impls above, whose value is
+        // still borrowed. The tokens alone carry nothing, so a foreign newtype using them finds
+        // no reference or one of the wrong type. The `set` functions add their own reference, so
+        // the wrappers must not be dropped.
+        unsafe {
+            match (name, PASSTHROUGH.take()) {
+                (NODE_TOKEN, Some(Passthrough::Node(ptr))) => {
+                    let node = ManuallyDrop::new(Node::from_ptr(ptr));
+                    self.map.append_node_raw_unchecked(self.key, &node)
+                }
+                (FRAME_TOKEN, Some(Passthrough::Frame(ptr))) => {
+                    let frame = ManuallyDrop::new(Frame::from_ptr(ptr));
+                    self.map.append_frame_raw_unchecked(self.key, &frame)
+                }
+                (FUNCTION_TOKEN, Some(Passthrough::Function(ptr))) => {
+                    let func = ManuallyDrop::new(Function::from_ptr(ptr));
+                    self.map.append_function_raw_unchecked(self.key, &func)
+                }
+                _ => Err(MapError::custom(format!(
+                    "`{name}` is reserved for rustsynth's own types"
+                ))),
+            }
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: &T,
+    ) -> MapResult<()> {
+        Err(self.nested_error(&format!("enum variant `{variant}` with data")))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> MapResult<Self> {
+        if self.in_seq {
+            return Err(self.nested_error("a nested sequence"));
+        }
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> MapResult<Self> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> MapResult<Self> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeTupleVariant> {
+        Err(self.nested_error(&format!("enum variant `{variant}` with data")))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> MapResult<Self::SerializeMap> {
+        Err(self.nested_error("a nested map"))
+    }
+
+    fn serialize_struct(self, name: &'static str, _: usize) -> MapResult<Self::SerializeStruct> {
+        Err(self.nested_error(&format!("nested struct `{name}`")))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> MapResult<Self::SerializeStructVariant> {
+        Err(self.nested_error(&format!("enum variant `{variant}` with data")))
+    }
+}
+
+impl ser::SerializeSeq for ValueSerializer<'_, '_, '_> {
+    type Ok = ();
+    type Error = MapError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> MapResult<()> {
+        value.serialize(ValueSerializer {
+            map: &mut *self.map,
+            key: self.key,
+            in_seq: true,
+        })
+    }
+
+    fn end(self) -> MapResult<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for ValueSerializer<'_, '_, '_> {
+    type Ok = ();
+    type Error = MapError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> MapResult<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> MapResult<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for ValueSerializer<'_, '_, '_> {
+    type Ok = ();
+    type Error = MapError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> MapResult<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> MapResult<()> {
+        Ok(())
+    }
+}
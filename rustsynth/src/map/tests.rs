@@ -131,4 +131,171 @@ mod tests {
         assert_eq!(map.get::<f64>("float").unwrap(), std::f64::consts::PI);
         assert_eq!(map.get::<String>("string").unwrap(), "test");
     }
+
+    #[test]
+    fn test_map_iter() {
+        use crate::map::{DataType, MapValues};
+
+        let _api = setup_api();
+        let mut map = Map::new().unwrap();
+        map.set_int_array("ints", vec![1, 2, 3]).unwrap();
+        map.set_float("float", 0.5).unwrap();
+        map.set_string("name", "bt709").unwrap();
+
+        let entries: Vec<_> = map.iter().collect();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains(&("ints", MapValues::Ints(&[1, 2, 3]))));
+        assert!(entries.contains(&("float", MapValues::Floats(&[0.5]))));
+
+        match map.values("name").unwrap() {
+            MapValues::Data(data) => {
+                assert_eq!(data.len(), 1);
+                assert_eq!(&*data[0].0, b"bt709");
+                assert_eq!(data[0].1, DataType::String);
+            }
+            other => panic!("unexpected values {other:?}"),
+        }
+
+        assert_eq!(map.values("ints").unwrap().to_string(), "[1, 2, 3]");
+        assert_eq!(map.values("name").unwrap().to_string(), "\"bt709\"");
+        let printed = map.to_string();
+        assert!(printed.contains("ints: [1, 2, 3]\n"));
+        assert!(printed.contains("float: 0.5\n"));
+    }
+
+    #[test]
+    fn test_map_clone_and_merge() {
+        let _api = setup_api();
+        let mut map = Map::new().unwrap();
+        map.set_int("a", 1).unwrap();
+        map.set_int("b", 2).unwrap();
+
+        let copy = map.clone();
+        map.set_int("a", 10).unwrap();
+        assert_eq!(copy.get::<i64>("a").unwrap(), 1);
+        assert_eq!(copy.get::<i64>("b").unwrap(), 2);
+
+        let mut other = Map::new().unwrap();
+        other.set_int("b", 20).unwrap();
+        other.set_int("c", 30).unwrap();
+
+        let mut kept = copy.clone();
+        kept.merge(&other, false).unwrap();
+        assert_eq!(kept.get::<i64>("b").unwrap(), 2);
+        assert_eq!(kept.get::<i64>("c").unwrap(), 30);
+
+        let mut replaced = copy.clone();
+        replaced.merge(&other, true).unwrap();
+        assert_eq!(replaced.get::<i64>("a").unwrap(), 1);
+        assert_eq!(replaced.get::<i64>("b").unwrap(), 20);
+        assert_eq!(replaced.get::<i64>("c").unwrap(), 30);
+    }
+
+    #[test]
+    fn test_map_data_type_hints() {
+        use crate::map::DataType;
+
+        let _api = setup_api();
+        let mut map = Map::new().unwrap();
+        map.set_string("text", "hello").unwrap();
+        map.set_binary("blob", &[0, 1, 2]).unwrap();
+        map.append_string("text", "world").unwrap();
+        map.append_binary("blob", &[3]).unwrap();
+
+        assert_eq!(map.data_type_hint(c"text", 1), DataType::String);
+        assert_eq!(map.data_type_hint(c"blob", 1), DataType::Binary);
+        assert_eq!(
+            map.get_string_iter("text").unwrap().collect::<Vec<_>>(),
+            vec!["hello", "world"]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "lowercase")]
+        enum Kernel {
+            Bicubic,
+            Lanczos,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Args {
+            width: i64,
+            sigma: f64,
+            planes: Vec<i64>,
+            name: String,
+            enabled: bool,
+            kernel: Kernel,
+            matrix: Option<String>,
+        }
+
+        let _api = setup_api();
+        let args = Args {
+            width: 1920,
+            sigma: 0.5,
+            planes: vec![0],
+            name: "test".to_string(),
+            enabled: true,
+            kernel: Kernel::Lanczos,
+            matrix: None,
+        };
+
+        let map = crate::map::to_map(&args).unwrap();
+        assert_eq!(map.key_count(), 6);
+        assert_eq!(map.get_int("width").unwrap(), 1920);
+        assert_eq!(map.get_int_array("planes").unwrap(), &[0]);
+        assert_eq!(map.get_int("enabled").unwrap(), 1);
+        assert_eq!(map.get_string("kernel").unwrap(), "lanczos");
+
+        let back: Args = crate::map::from_map(&map).unwrap();
+        assert_eq!(back, args);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_nested_maps() {
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        struct Inner {
+            x: i64,
+        }
+
+        #[derive(Serialize)]
+        struct Outer {
+            inner: Inner,
+        }
+
+        let _api = setup_api();
+        assert!(crate::map::to_map(&Outer {
+            inner: Inner { x: 1 }
+        })
+        .is_err());
+        assert!(crate::map::to_map(&vec![1i64, 2]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_forged_handles() {
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        #[serde(rename = "$rustsynth::private::Node")]
+        struct Forged(u64);
+
+        #[derive(Serialize)]
+        struct Args {
+            clip: Forged,
+        }
+
+        let _api = setup_api();
+        assert!(crate::map::to_map(&Args {
+            clip: Forged(0xdead_beef)
+        })
+        .is_err());
+    }
 }
@@ -1,9 +1,11 @@
+use std::fmt;
+
 use crate::frame::Frame;
 use crate::function::Function;
 use crate::map::{MapRef, MapResult, ValueIter};
 use crate::node::Node;
 
-use super::data::Data;
+use super::data::{Data, DataType};
 
 /// An enumeration of all possible value types.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -16,6 +18,122 @@ pub enum ValueType {
     Function,
 }
 
+/// All values stored under one key of a map, as yielded by [`MapRef::iter()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapValues<'map, 'elem> {
+    Ints(&'map [i64]),
+    Floats(&'map [f64]),
+    /// Data elements along with their type hint.
+    Data(Vec<(Data<'elem>, DataType)>),
+    Nodes(Vec<Node<'elem>>),
+    Frames(Vec<Frame<'elem>>),
+    Functions(Vec<Function<'elem>>),
+}
+
+impl MapValues<'_, '_> {
+    /// Returns the type of the values.
+    #[must_use]
+    pub const fn value_type(&self) -> ValueType {
+        match self {
+            Self::Ints(_) => ValueType::Int,
+            Self::Floats(_) => ValueType::Float,
+            Self::Data(_) => ValueType::Data,
+            Self::Nodes(_) => ValueType::Node,
+            Self::Frames(_) => ValueType::Frame,
+            Self::Functions(_) => ValueType::Function,
+        }
+    }
+
+    /// Returns the number of values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Ints(v) => v.len(),
+            Self::Floats(v) => v.len(),
+            Self::Data(v) => v.len(),
+            Self::Nodes(v) => v.len(),
+            Self::Frames(v) => v.len(),
+            Self::Functions(v) => v.len(),
+        }
+    }
+
+    /// Returns `true` if the key holds no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn fmt_data(f: &mut fmt::Formatter<'_>, data: &Data<'_>, hint: DataType) -> fmt::Result {
+    match (hint, std::str::from_utf8(data)) {
+        (DataType::String | DataType::Unknown, Ok(s)) => write!(f, "{s:?}"),
+        _ => write!(f, "<{} bytes>", data.len()),
+    }
+}
+
+fn fmt_node(f: &mut fmt::Formatter<'_>, node: &Node<'_>) -> fmt::Result {
+    if let Some(vi) = node.video_info() {
+        write!(
+            f,
+            "<video node {}x{}, {} frames>",
+            vi.width, vi.height, vi.num_frames
+        )
+    } else if let Some(ai) = node.audio_info() {
+        write!(
+            f,
+            "<audio node {} Hz, {} samples>",
+            ai.sample_rate, ai.num_samples
+        )
+    } else {
+        f.write_str("<node>")
+    }
+}
+
+fn fmt_frame(f: &mut fmt::Formatter<'_>, frame: &Frame<'_>) -> fmt::Result {
+    if frame.get_video_format().is_some() {
+        write!(
+            f,
+            "<video frame {}x{}>",
+            frame.get_width(0),
+            frame.get_height(0)
+        )
+    } else {
+        f.write_str("<audio frame>")
+    }
+}
+
+/// Formats a single value as is, and multiple values as a bracketed list.
+fn fmt_list<T>(
+    f: &mut fmt::Formatter<'_>,
+    values: &[T],
+    mut fmt_one: impl FnMut(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    if let [value] = values {
+        return fmt_one(f, value);
+    }
+    f.write_str("[")?;
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        fmt_one(f, value)?;
+    }
+    f.write_str("]")
+}
+
+impl fmt::Display for MapValues<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ints(v) => fmt_list(f, v, |f, x| write!(f, "{x}")),
+            Self::Floats(v) => fmt_list(f, v, |f, x| write!(f, "{x}")),
+            Self::Data(v) => fmt_list(f, v, |f, (data, hint)| fmt_data(f, data, *hint)),
+            Self::Nodes(v) => fmt_list(f, v, fmt_node),
+            Self::Frames(v) => fmt_list(f, v, fmt_frame),
+            Self::Functions(v) => fmt_list(f, v, |f, _| f.write_str("<function>")),
+        }
+    }
+}
+
 /// A trait for values which can be stored in a map.
 pub trait ValueNotArray<'map, 'elem: 'map>: Sized {
     /// Retrieves an iterator over the values from the map.
@@ -232,7 +350,7 @@ impl<'map, 'elem: 'map> Value<'map, 'elem> for String {
 
     #[inline]
     fn store_in_map(map: &'map mut MapRef, key: &str, x: &Self) -> MapResult<()> {
-        map.set_data(key, x.as_bytes())
+        map.set_string(key, x)
     }
 }
 
@@ -247,6 +365,6 @@ impl<'map, 'elem: 'map> ValueNotArray<'map, 'elem> for String {
 
     #[inline]
     fn append_to_map(map: &'map mut MapRef, key: &str, x: &Self) -> MapResult<()> {
-        map.append_data(key, x.as_bytes())
+        map.append_string(key, x)
     }
 }
@@ -19,7 +19,7 @@ mod errors;
 pub use self::errors::GetFrameError;
 
 /// A reference to a node in the constructed filter graph.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Node<'core> {
     handle: NonNull<ffi::VSNode>,
     _owner: std::marker::PhantomData<&'core ()>,
@@ -3,32 +3,48 @@ use bitflags::bitflags;
 use ffi::VSPluginFunction;
 use rustsynth_sys::{self as ffi, VSPluginConfigFlags};
 use std::{
-    ffi::{c_void, CStr, CString, NulError},
+    ffi::{c_char, c_void, CStr, CString, NulError},
     marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
     ptr::{self, NonNull},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
 };
 use thiserror::Error;
 
 use crate::{
     api::API,
     core::CoreRef,
+    function::panic_message,
     map::{Map, MapError, MapRef},
+    signature::{Signature, SignatureError},
 };
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Error, Debug)]
 pub enum PluginError {
     #[error("Function '{0}' not found in plugin")]
     FunctionNotFound(String),
+    #[error("No plugin loaded with namespace '{0}'")]
+    PluginNotFound(String),
     #[error("Plugin function call failed: {0}")]
     FunctionCallFailed(#[from] PluginFunctionError),
     #[error("Error: {0}")]
     InvokeError(String),
     #[error("Output map error: {0}")]
     OutputMapError(MapError),
+    #[error("Argument map error: {0}")]
+    ArgumentMapError(#[from] MapError),
     #[error("CString conversion error: {0}")]
     CStringConversion(#[from] NulError),
     #[error("Failed to register function")]
     RegistrationFailed,
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(#[from] SignatureError),
 }
 
 /// A `VapourSynth` plugin.
@@ -168,13 +184,16 @@ impl<'core> Plugin<'core> {
     }
 
     /// Tries to invoke a plugin function, returning a Result instead of panicking
+    ///
+    /// The arguments are checked by `VapourSynth` itself. To check them up front with typed errors,
+    /// call [`Signature::validate()`] on [`PluginFunction::signature()`] first.
     pub fn try_invoke(&self, name: &str, args: &Map<'core>) -> PluginResult<Map<'core>> {
         let func = self
             .function(name)
             .ok_or_else(|| PluginError::FunctionNotFound(name.to_string()))?;
         let ret = func.call(args)?;
-        if let Ok(err) = ret.error() {
-            return Err(PluginError::InvokeError(err.to_string()));
+        if let Some(err) = ret.error() {
+            return Err(PluginError::InvokeError(err.into_owned()));
         }
         Ok(ret)
     }
@@ -191,34 +210,152 @@ impl<'core> Plugin<'core> {
         self.try_invoke(name, &empty_map)
     }
 
-    /// Function that registers a filter exported by the plugin. A plugin can export any number of filters. This function may only be called during the plugin loading phase unless the [`PluginConfigFlags::MODIFIABLE`] flag was set.
-    pub fn register_function(
+    /// Registers a function exported by the plugin. A plugin can export any number of functions. This function may only be called during the plugin loading phase unless the [`PluginConfigFlags::MODIFIABLE`] flag was set.
+    ///
+    /// `func` may capture state and is called by reference for every invocation. A panic inside it
+    /// is caught and set as the error of the output map.
+    ///
+    /// `func` is dropped exactly once, when the core of the plugin is destroyed. `VapourSynth` has
+    /// no free callback for registered functions and the plugin doesn't know its core, so `func`
+    /// is handed to the core the first time it is called, or when rustsynth frees the core with
+    /// [`CoreRef::free_core()`] or by dropping its script environment. A function that is never
+    /// called on a core freed elsewhere, such as by vspipe, may be kept until the process exits.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rustsynth::plugin::{Plugin, PluginResult};
+    /// # fn f(plugin: &Plugin<'_>) -> PluginResult<()> {
+    /// let offset = 10;
+    /// plugin.register_function("AddOffset", "n:int;", "n:int;", move |in_map, out_map, _core| {
+    ///     match in_map.get_int("n") {
+    ///         Ok(n) => out_map.set_int("n", n + offset),
+    ///         Err(err) => out_map.set_error(&err.to_string()),
+    ///     }
+    ///     .ok();
+    /// })
+    /// # }
+    /// ```
+    pub fn register_function<F>(
         &self,
         name: &str,
         args: &str,
         ret_type: &str,
-        func: PublicFunction,
-    ) -> PluginResult<()> {
+        func: F,
+    ) -> PluginResult<()>
+    where
+        F: Fn(&MapRef<'_>, &mut MapRef<'_>, CoreRef<'_>) + Send + Sync + 'static,
+    {
         let name_c = CString::new(name)?;
         let args_c = CString::new(args)?;
         let ret_type_c = CString::new(ret_type)?;
-        let user_data: Box<PublicFunction> = Box::new(func);
-        let user_data_ptr = Box::into_raw(user_data).cast::<c_void>();
+        let registered = Box::into_raw(Box::new(RegisteredFunction {
+            plugin: self.as_ptr(),
+            owned: AtomicBool::new(false),
+            func: Box::new(func),
+        }));
         let res = unsafe {
             API::get_cached().register_function(
                 name_c.as_ptr(),
                 args_c.as_ptr(),
                 ret_type_c.as_ptr(),
                 Some(public_function),
-                user_data_ptr,
+                registered.cast::<c_void>(),
                 self.handle.as_ptr(),
             )
         };
         if res == 0 {
-            Ok(())
-        } else {
-            Err(PluginError::RegistrationFailed)
+            // the function was not registered, so nothing else holds the pointer
+            drop(unsafe { Box::from_raw(registered) });
+            return Err(PluginError::RegistrationFailed);
         }
+        UNOWNED_FUNCTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(UnownedFunction(registered));
+        Ok(())
+    }
+}
+
+/// A signature compatible with [`Plugin::register_function()`].
+pub type PublicFunction = fn(in_map: &MapRef<'_>, out_map: &mut MapRef<'_>, core: CoreRef);
+
+type BoxedPublicFunction = Box<dyn Fn(&MapRef<'_>, &mut MapRef<'_>, CoreRef<'_>) + Send + Sync>;
+
+/// A function passed to [`Plugin::register_function()`], the user data of `public_function()`.
+struct RegisteredFunction {
+    plugin: *mut ffi::VSPlugin,
+    /// Whether a core owns the function, see [`hand_over_registered_functions()`].
+    owned: AtomicBool,
+    func: BoxedPublicFunction,
+}
+
+/// A registered function that no core owns yet.
+///
+/// Its plugin may have been freed along with a core rustsynth never saw, and the address reused by
+/// another plugin. The function then goes to that plugin's core, which only drops it later.
+struct UnownedFunction(*mut RegisteredFunction);
+
+// SAFETY: the boxed function is `Send + Sync`
+unsafe impl Send for UnownedFunction {}
+
+static UNOWNED_FUNCTIONS: Mutex<Vec<UnownedFunction>> = Mutex::new(Vec::new());
+
+/// Hands the registered functions of the plugins of `core` that no core owns yet to `core`.
+///
+/// They are kept by a log handler that does nothing, whose free callback drops them once the core
+/// is destroyed, whoever frees it. Like any log handler it receives the messages the core cached
+/// while none was attached.
+pub(crate) fn hand_over_registered_functions(core: &CoreRef<'_>) {
+    if UNOWNED_FUNCTIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_empty()
+    {
+        return;
+    }
+    let plugins: Vec<_> = core.plugins().map(|plugin| plugin.as_ptr()).collect();
+    hand_over_plugin_functions(&plugins, core.as_ptr());
+}
+
+fn hand_over_plugin_functions(plugins: &[*mut ffi::VSPlugin], core: *mut ffi::VSCore) {
+    let functions: Vec<*mut RegisteredFunction> = {
+        let mut unowned = UNOWNED_FUNCTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let (owned, kept): (Vec<_>, Vec<_>) = unowned
+            .drain(..)
+            .partition(|f| plugins.contains(&unsafe { (*f.0).plugin }));
+        *unowned = kept;
+        owned.into_iter().map(|f| f.0).collect()
+    };
+    if functions.is_empty() {
+        return;
+    }
+    for &f in &functions {
+        unsafe { (*f).owned.store(true, Ordering::Release) };
+    }
+    let functions = Box::into_raw(Box::new(functions));
+    unsafe {
+        API::get_cached().add_log_handler(
+            ignore_log_message,
+            Some(drop_registered_functions),
+            functions.cast::<c_void>(),
+            core,
+        );
+    }
+}
+
+unsafe extern "C" fn ignore_log_message(
+    _msg_type: i32,
+    _msg: *const c_char,
+    _user_data: *mut c_void,
+) {
+}
+
+unsafe extern "C" fn drop_registered_functions(user_data: *mut c_void) {
+    let functions = unsafe { Box::from_raw(user_data.cast::<Vec<*mut RegisteredFunction>>()) };
+    for f in *functions {
+        drop(unsafe { Box::from_raw(f) });
     }
 }
 
@@ -229,18 +366,26 @@ unsafe extern "C" fn public_function(
     core: *mut ffi::VSCore,
     _vs_api: *const ffi::VSAPI,
 ) {
-    if in_map.is_null() || user_data.is_null() || core.is_null() {
+    if in_map.is_null() || out_map.is_null() || user_data.is_null() || core.is_null() {
         return;
     }
-    let user_data = unsafe { Box::from_raw(user_data.cast::<PublicFunction>()) };
+    // Only borrowed, the function is dropped by its core
+    let registered = unsafe { &*user_data.cast::<RegisteredFunction>() };
+    if !registered.owned.load(Ordering::Acquire) {
+        // A plugin's functions are only called on its own core
+        hand_over_plugin_functions(&[registered.plugin], core);
+    }
     let in_map = unsafe { MapRef::from_ptr(in_map) };
     let out_map = unsafe { MapRef::from_ptr_mut(out_map) };
     let core = unsafe { CoreRef::from_ptr(core) };
-    (user_data)(in_map, out_map, core);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        (registered.func)(in_map, &mut *out_map, core)
+    }));
+    if let Err(payload) = result {
+        let _ = out_map.set_error(&format!("Function panicked: {}", panic_message(&*payload)));
+    }
 }
 
-pub type PublicFunction = fn(in_map: &MapRef<'_>, out_map: &mut MapRef<'_>, core: CoreRef);
-
 bitflags! {
     pub struct PluginConfigFlags: i32 {
         /// Allow functions to be added to the plugin object after the plugin loading phase. Mostly useful for Avisynth compatibility and other foreign plugin loaders.
@@ -326,6 +471,18 @@ impl<'a> PluginFunction<'a> {
         }
     }
 
+    /// The parsed argument signature of the function
+    #[must_use]
+    pub fn signature(&self) -> Option<Signature> {
+        self.get_arguments()?.parse().ok()
+    }
+
+    /// The parsed return type of the function
+    #[must_use]
+    pub fn return_signature(&self) -> Option<Signature> {
+        self.get_return_type()?.parse().ok()
+    }
+
     pub fn call<'map>(&self, args: &Map<'map>) -> Result<Map<'map>, PluginFunctionError> {
         let name = self.get_name().ok_or(PluginFunctionError::NoName)?;
         let name_c = CString::new(name).map_err(PluginFunctionError::NulError)?;
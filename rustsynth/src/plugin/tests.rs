@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use crate::api::API;
+    use crate::core::{CoreBuilder, CoreRef};
+    use crate::map::Map;
+    use crate::plugin::{
+        public_function, BoxedPublicFunction, PluginError, RegisteredFunction, UnownedFunction,
+        UNOWNED_FUNCTIONS,
+    };
+
+    fn setup_api() -> API {
+        API::get().expect("Failed to get VapourSynth API")
+    }
+
+    #[test]
+    fn test_register_function_on_loaded_plugin() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let std = core.std().unwrap();
+
+        // Functions can only be added while a plugin loads, so both a new and an existing name
+        // are refused once it is loaded
+        for name in ["RustsynthAnswer", "BlankClip"] {
+            let result = std.register_function(name, "", "n:int;", |_, out_map, _| {
+                out_map.set_int("n", 42).unwrap();
+            });
+            assert!(matches!(result, Err(PluginError::RegistrationFailed)));
+        }
+        assert!(std.function("RustsynthAnswer").is_none());
+        assert!(matches!(
+            std.try_invoke("RustsynthAnswer", &Map::new().unwrap()),
+            Err(PluginError::FunctionNotFound(_))
+        ));
+    }
+
+    /// Wraps `func` the way `Plugin::register_function()` does, owned by a core already.
+    fn registered(func: BoxedPublicFunction) -> RegisteredFunction {
+        RegisteredFunction {
+            plugin: ptr::null_mut(),
+            owned: AtomicBool::new(true),
+            func,
+        }
+    }
+
+    /// Calls `func` the way `VapourSynth` calls a registered function.
+    fn call_registered(
+        func: *const RegisteredFunction,
+        in_map: &Map<'_>,
+        core: &CoreRef<'_>,
+    ) -> Map<'static> {
+        let mut out_map = Map::new().unwrap();
+        unsafe {
+            public_function(
+                in_map.as_ptr(),
+                out_map.as_mut_ptr(),
+                func.cast_mut().cast(),
+                core.as_ptr(),
+                ptr::null(),
+            );
+        }
+        out_map
+    }
+
+    #[test]
+    fn test_registered_function_call() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let offset = 10;
+        let func = registered(Box::new(move |in_map, out_map, _| {
+            let n = in_map.get_int("n").unwrap();
+            out_map.set_int("n", n + offset).unwrap();
+        }));
+        let mut in_map = Map::new().unwrap();
+        in_map.set_int("n", 32).unwrap();
+
+        let out_map = call_registered(&func, &in_map, &core);
+        assert!(out_map.error().is_none());
+        assert_eq!(out_map.get_int("n").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_registered_function_panic() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let func = registered(Box::new(|_, _, _| panic!("no answer")));
+
+        let out_map = call_registered(&func, &Map::new().unwrap(), &core);
+        assert_eq!(
+            out_map.error().as_deref(),
+            Some("Function panicked: no answer")
+        );
+    }
+
+    #[test]
+    fn test_registered_functions_dropped_with_core() {
+        let _api = setup_api();
+        let core = CoreBuilder::new().disable_auto_loading().build();
+        let plugin = core.std().unwrap().as_ptr();
+        let state = Arc::new(());
+        // As left by `Plugin::register_function()`, one is called and the other isn't
+        let [called, uncalled] = [(); 2].map(|()| {
+            let state = Arc::clone(&state);
+            let func = Box::into_raw(Box::new(RegisteredFunction {
+                plugin,
+                owned: AtomicBool::new(false),
+                func: Box::new(move |_, _, _| drop(Arc::clone(&state))),
+            }));
+            UNOWNED_FUNCTIONS
+                .lock()
+                .unwrap()
+                .push(UnownedFunction(func));
+            func
+        });
+        assert_eq!(Arc::strong_count(&state), 3);
+
+        let out_map = call_registered(called, &Map::new().unwrap(), &core);
+        assert!(out_map.error().is_none());
+        // Calling one hands every function of the plugin over to the core
+        assert!(unsafe { (*called).owned.load(Ordering::Acquire) });
+        assert!(unsafe { (*uncalled).owned.load(Ordering::Acquire) });
+        assert_eq!(Arc::strong_count(&state), 3);
+
+        unsafe { core.free_core() };
+        assert_eq!(Arc::strong_count(&state), 1);
+    }
+}
@@ -0,0 +1,43 @@
+use std::result;
+
+use thiserror::Error;
+
+use crate::map::MapError;
+
+use super::ArgType;
+
+/// The error type for parsing signatures and validating arguments against them.
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum SignatureError {
+    #[error("Argument '{0}' is missing a type")]
+    MissingType(String),
+    #[error("Argument '{name}' has an unknown type '{ty}'")]
+    UnknownType { name: String, ty: String },
+    #[error("Argument '{name}' has an unknown flag '{flag}'")]
+    UnknownFlag { name: String, flag: String },
+    #[error("'{0}' is not a valid argument name")]
+    InvalidName(String),
+    #[error("Argument '{0}' is declared more than once")]
+    DuplicateArgument(String),
+    #[error("'any' must be the last entry of a signature")]
+    MisplacedAny,
+    #[error("Missing required argument '{0}'")]
+    MissingArgument(String),
+    #[error("Argument '{name}' must be of type {expected}, got {found}")]
+    WrongType {
+        name: String,
+        expected: ArgType,
+        found: ArgType,
+    },
+    #[error("Argument '{name}' takes a single value, got {count}")]
+    NotAnArray { name: String, count: usize },
+    #[error("Argument '{0}' can't be empty")]
+    EmptyArgument(String),
+    #[error("Unexpected argument '{0}'")]
+    UnexpectedArgument(String),
+    #[error("Map error: {0}")]
+    Map(#[from] MapError),
+}
+
+/// A specialized `Result` type for signature operations.
+pub type SignatureResult<T> = result::Result<T, SignatureError>;
@@ -0,0 +1,373 @@
+//! Parsing and validation of `VapourSynth` argument signatures.
+//!
+//! Plugin functions describe their arguments and return values with strings such as
+//! `clip:vnode;planes:int[]:opt;`. Every entry is a name, a type with an optional `[]` array
+//! suffix and any number of `opt` and `empty` flags. A final `any` entry accepts arguments that
+//! aren't listed.
+//!
+//! # Example
+//! ```
+//! use rustsynth::signature::{ArgType, Signature};
+//!
+//! let signature: Signature = "clip:vnode;planes:int[]:opt;".parse().unwrap();
+//! assert_eq!(signature.arguments[1].arg_type, ArgType::Int);
+//! assert!(signature.arguments[1].array);
+//! assert_eq!(signature.to_string(), "clip:vnode;planes:int[]:opt;");
+//! ```
+use std::{fmt, os::raw::c_int, str::FromStr};
+
+use rustsynth_sys as ffi;
+
+use crate::map::{MapError, MapRef};
+
+mod errors;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::errors::{SignatureError, SignatureResult};
+
+/// The type of an argument.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ArgType {
+    Int,
+    Float,
+    Data,
+    AudioNode,
+    VideoNode,
+    AudioFrame,
+    VideoFrame,
+    Function,
+}
+
+impl ArgType {
+    /// Returns the name of the type as used in signatures.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Data => "data",
+            Self::AudioNode => "anode",
+            Self::VideoNode => "vnode",
+            Self::AudioFrame => "aframe",
+            Self::VideoFrame => "vframe",
+            Self::Function => "func",
+        }
+    }
+
+    const fn from_bytes(name: &[u8]) -> Option<Self> {
+        match name {
+            b"int" => Some(Self::Int),
+            b"float" => Some(Self::Float),
+            b"data" => Some(Self::Data),
+            b"anode" => Some(Self::AudioNode),
+            b"vnode" => Some(Self::VideoNode),
+            b"aframe" => Some(Self::AudioFrame),
+            b"vframe" => Some(Self::VideoFrame),
+            b"func" => Some(Self::Function),
+            _ => None,
+        }
+    }
+
+    fn from_property_type(ty: c_int) -> Option<Self> {
+        use ffi::VSPropertyType as P;
+        match ty {
+            x if x == P::ptInt as c_int => Some(Self::Int),
+            x if x == P::ptFloat as c_int => Some(Self::Float),
+            x if x == P::ptData as c_int => Some(Self::Data),
+            x if x == P::ptAudioNode as c_int => Some(Self::AudioNode),
+            x if x == P::ptVideoNode as c_int => Some(Self::VideoNode),
+            x if x == P::ptAudioFrame as c_int => Some(Self::AudioFrame),
+            x if x == P::ptVideoFrame as c_int => Some(Self::VideoFrame),
+            x if x == P::ptFunction as c_int => Some(Self::Function),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ArgType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single argument of a signature.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Argument {
+    pub name: String,
+    pub arg_type: ArgType,
+    /// The argument takes any number of values (`[]`).
+    pub array: bool,
+    /// The argument may be left out (`:opt`).
+    pub optional: bool,
+    /// An array argument may be set without any values (`:empty`).
+    pub empty: bool,
+}
+
+impl Argument {
+    fn parse(entry: &str) -> SignatureResult<Self> {
+        let mut parts = entry.split(':');
+        let name = parts.next().unwrap_or_default();
+        if !is_identifier(name.as_bytes()) {
+            return Err(SignatureError::InvalidName(name.to_owned()));
+        }
+
+        let ty = parts
+            .next()
+            .ok_or_else(|| SignatureError::MissingType(name.to_owned()))?;
+        let (ty, array) = match ty.strip_suffix("[]") {
+            Some(ty) => (ty, true),
+            None => (ty, false),
+        };
+        let arg_type =
+            ArgType::from_bytes(ty.as_bytes()).ok_or_else(|| SignatureError::UnknownType {
+                name: name.to_owned(),
+                ty: ty.to_owned(),
+            })?;
+
+        let mut argument = Self {
+            name: name.to_owned(),
+            arg_type,
+            array,
+            optional: false,
+            empty: false,
+        };
+        for flag in parts {
+            match flag {
+                "opt" => argument.optional = true,
+                "empty" => argument.empty = true,
+                _ => {
+                    return Err(SignatureError::UnknownFlag {
+                        name: argument.name,
+                        flag: flag.to_owned(),
+                    })
+                }
+            }
+        }
+        Ok(argument)
+    }
+
+    fn validate(&self, map: &MapRef<'_>) -> SignatureResult<()> {
+        let key = MapRef::make_raw_key(&self.name)?;
+        let count = match unsafe { map.value_count_raw_unchecked(&key) } {
+            Ok(count) => count,
+            Err(MapError::KeyNotFound) if self.optional => return Ok(()),
+            Err(MapError::KeyNotFound) => {
+                return Err(SignatureError::MissingArgument(self.name.clone()))
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        let ty = unsafe { map.property_type_raw_unchecked(&key) };
+        let found = ArgType::from_property_type(ty).ok_or(MapError::KeyNotFound)?;
+        if found != self.arg_type {
+            return Err(SignatureError::WrongType {
+                name: self.name.clone(),
+                expected: self.arg_type,
+                found,
+            });
+        }
+
+        if count == 0 && !self.empty {
+            return Err(SignatureError::EmptyArgument(self.name.clone()));
+        }
+        if count > 1 && !self.array {
+            return Err(SignatureError::NotAnArray {
+                name: self.name.clone(),
+                count,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Argument {
+    type Err = SignatureError;
+
+    fn from_str(s: &str) -> SignatureResult<Self> {
+        Self::parse(s.strip_suffix(';').unwrap_or(s))
+    }
+}
+
+impl fmt::Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.name, self.arg_type)?;
+        if self.array {
+            f.write_str("[]")?;
+        }
+        if self.optional {
+            f.write_str(":opt")?;
+        }
+        if self.empty {
+            f.write_str(":empty")?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed argument signature, as returned by
+/// [`PluginFunction::get_arguments()`](crate::plugin::PluginFunction::get_arguments) or given as
+/// [`Filter::ARGS`](crate::filter::Filter::ARGS).
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Signature {
+    pub arguments: Vec<Argument>,
+    /// Arguments that aren't listed are accepted as well (`any`).
+    pub any: bool,
+}
+
+impl Signature {
+    /// Parses a signature string.
+    pub fn parse(signature: &str) -> SignatureResult<Self> {
+        let mut parsed = Self::default();
+        for entry in signature.split(';').filter(|entry| !entry.is_empty()) {
+            if parsed.any {
+                return Err(SignatureError::MisplacedAny);
+            }
+            if entry == "any" {
+                parsed.any = true;
+                continue;
+            }
+
+            let argument = Argument::parse(entry)?;
+            if parsed.argument(&argument.name).is_some() {
+                return Err(SignatureError::DuplicateArgument(argument.name));
+            }
+            parsed.arguments.push(argument);
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the argument with the given name.
+    #[must_use]
+    pub fn argument(&self, name: &str) -> Option<&Argument> {
+        self.arguments.iter().find(|argument| argument.name == name)
+    }
+
+    /// Checks that `map` holds every required argument with the right type and number of values,
+    /// and nothing else unless the signature accepts `any`.
+    pub fn validate(&self, map: &MapRef<'_>) -> SignatureResult<()> {
+        for argument in &self.arguments {
+            argument.validate(map)?;
+        }
+
+        if !self.any {
+            for key in map.keys() {
+                if self.argument(key).is_none() {
+                    return Err(SignatureError::UnexpectedArgument(key.to_owned()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Signature {
+    type Err = SignatureError;
+
+    fn from_str(s: &str) -> SignatureResult<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for argument in &self.arguments {
+            write!(f, "{argument};")?;
+        }
+        if self.any {
+            f.write_str("any;")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the syntax of a signature at compile time.
+///
+/// This accepts the same strings as [`Signature::parse()`] except that duplicate argument names
+/// aren't detected.
+///
+/// # Example
+/// ```
+/// use rustsynth::signature;
+///
+/// const ARGS: &str = "clip:vnode;planes:int[]:opt;";
+/// const _: () = assert!(signature::is_valid(ARGS));
+/// assert!(!signature::is_valid("clip:node;"));
+/// ```
+#[must_use]
+pub const fn is_valid(signature: &str) -> bool {
+    let bytes = signature.as_bytes();
+    let mut any = false;
+    let mut start = 0;
+    while start < bytes.len() {
+        let end = find(bytes, start, b';');
+        if end > start {
+            let entry = slice(bytes, start, end);
+            if any {
+                return false;
+            }
+            if matches!(entry, b"any") {
+                any = true;
+            } else if !is_valid_argument(entry) {
+                return false;
+            }
+        }
+        start = end + 1;
+    }
+    true
+}
+
+const fn is_valid_argument(entry: &[u8]) -> bool {
+    let name_end = find(entry, 0, b':');
+    if name_end == entry.len() || !is_identifier(slice(entry, 0, name_end)) {
+        return false;
+    }
+
+    let type_end = find(entry, name_end + 1, b':');
+    let mut ty = slice(entry, name_end + 1, type_end);
+    if let [rest @ .., b'[', b']'] = ty {
+        ty = rest;
+    }
+    if ArgType::from_bytes(ty).is_none() {
+        return false;
+    }
+
+    let mut start = type_end;
+    while start < entry.len() {
+        let end = find(entry, start + 1, b':');
+        match slice(entry, start + 1, end) {
+            b"opt" | b"empty" => {}
+            _ => return false,
+        }
+        start = end;
+    }
+    true
+}
+
+/// Checks `name` against the rules of [`MapRef::is_key_valid()`].
+const fn is_identifier(name: &[u8]) -> bool {
+    if name.is_empty() || !(name[0].is_ascii_alphabetic() || name[0] == b'_') {
+        return false;
+    }
+    let mut i = 1;
+    while i < name.len() {
+        if !(name[i].is_ascii_alphanumeric() || name[i] == b'_') {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns the index of the first `needle` at or after `start`, or `bytes.len()`.
+const fn find(bytes: &[u8], mut start: usize, needle: u8) -> usize {
+    while start < bytes.len() && bytes[start] != needle {
+        start += 1;
+    }
+    start
+}
+
+const fn slice(bytes: &[u8], start: usize, end: usize) -> &[u8] {
+    bytes.split_at(end).0.split_at(start).1
+}
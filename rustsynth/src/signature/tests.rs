@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use crate::api::API;
+    use crate::map::Map;
+    use crate::signature::{self, ArgType, Argument, Signature, SignatureError};
+
+    #[test]
+    fn test_parse_signature() {
+        let signature = Signature::parse("clip:vnode;planes:int[]:opt;expr:data[]:opt:empty;")
+            .expect("Failed to parse signature");
+
+        assert!(!signature.any);
+        assert_eq!(
+            signature.arguments,
+            vec![
+                Argument {
+                    name: "clip".to_owned(),
+                    arg_type: ArgType::VideoNode,
+                    array: false,
+                    optional: false,
+                    empty: false,
+                },
+                Argument {
+                    name: "planes".to_owned(),
+                    arg_type: ArgType::Int,
+                    array: true,
+                    optional: true,
+                    empty: false,
+                },
+                Argument {
+                    name: "expr".to_owned(),
+                    arg_type: ArgType::Data,
+                    array: true,
+                    optional: true,
+                    empty: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        for s in [
+            "",
+            "clip:vnode;",
+            "clip:anode;gain:float[]:opt;",
+            "func:func;frame:vframe:opt;any;",
+        ] {
+            let signature: Signature = s.parse().unwrap();
+            assert_eq!(signature.to_string(), s);
+            assert!(signature::is_valid(s));
+        }
+
+        // The trailing semicolon is optional.
+        assert_eq!(
+            Signature::parse("clip:vnode").unwrap().to_string(),
+            "clip:vnode;"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("clip;", SignatureError::MissingType("clip".to_owned())),
+            (
+                "clip:node;",
+                SignatureError::UnknownType {
+                    name: "clip".to_owned(),
+                    ty: "node".to_owned(),
+                },
+            ),
+            (
+                "clip:vnode:optional;",
+                SignatureError::UnknownFlag {
+                    name: "clip".to_owned(),
+                    flag: "optional".to_owned(),
+                },
+            ),
+            (
+                "1clip:vnode;",
+                SignatureError::InvalidName("1clip".to_owned()),
+            ),
+            (
+                "a:int;a:float;",
+                SignatureError::DuplicateArgument("a".to_owned()),
+            ),
+            ("any;a:int;", SignatureError::MisplacedAny),
+        ];
+
+        for (s, error) in cases {
+            assert_eq!(Signature::parse(s), Err(error));
+            // Duplicates are only caught at runtime.
+            assert_eq!(signature::is_valid(s), s == "a:int;a:float;");
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let _api = API::get().expect("Failed to get VapourSynth API");
+        let signature = Signature::parse("a:int;b:float[]:opt;c:data[]:opt:empty;").unwrap();
+
+        let mut map = Map::new().unwrap();
+        assert_eq!(
+            signature.validate(&map),
+            Err(SignatureError::MissingArgument("a".to_owned()))
+        );
+
+        map.set_int("a", 1).unwrap();
+        assert_eq!(signature.validate(&map), Ok(()));
+
+        map.append_int("a", 2).unwrap();
+        assert_eq!(
+            signature.validate(&map),
+            Err(SignatureError::NotAnArray {
+                name: "a".to_owned(),
+                count: 2
+            })
+        );
+
+        map.set_int("a", 1).unwrap();
+        map.set_int("b", 1).unwrap();
+        assert_eq!(
+            signature.validate(&map),
+            Err(SignatureError::WrongType {
+                name: "b".to_owned(),
+                expected: ArgType::Float,
+                found: ArgType::Int,
+            })
+        );
+
+        map.set_float_array("b", vec![0.5, 1.5]).unwrap();
+        map.set_int("d", 0).unwrap();
+        assert_eq!(
+            signature.validate(&map),
+            Err(SignatureError::UnexpectedArgument("d".to_owned()))
+        );
+    }
+}
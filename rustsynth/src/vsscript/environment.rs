@@ -1,15 +1,20 @@
 use rustsynth_sys as ffi;
+use std::borrow::Cow;
+use std::env;
 use std::ffi::{CStr, CString};
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{self, Path, PathBuf};
 use std::ptr;
 use std::ptr::NonNull;
 
 use crate::core::CoreRef;
-use crate::map::Map;
+use crate::function::FunctionValue;
+use crate::map::{IntoOwnedMap, Map};
 use crate::node::Node;
 use crate::vsscript::errors::ScriptResult;
+#[cfg(feature = "script-api-42")]
+use crate::vsscript::ScriptOutput;
 use crate::{
     init_api,
     vsscript::{ScriptAPI, ScriptError},
@@ -17,19 +22,69 @@ use crate::{
 
 use crate::vsscript::VSScriptError;
 
-/// Contains two possible variants of arguments to `Environment::evaluate_script()`.
+/// Contains the possible variants of arguments to `Environment::evaluate_script()`.
 #[derive(Clone, Copy)]
 enum EvaluateScriptArgs<'a> {
     /// Evaluate a script contained in the string.
     Script(&'a str),
     /// Evaluate a script contained in the file.
     File(&'a Path),
+    /// Evaluate a script contained in the string as if it was read from the path.
+    Named(&'a str, &'a Path),
+}
+
+/// The working directory while a script is evaluated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum WorkingDirectory {
+    /// Leave the working directory of the process as it is.
+    #[default]
+    Keep,
+    /// Change to the directory of the script file, or its virtual path. Scripts evaluated without
+    /// a path keep the working directory.
+    ScriptDir,
+    /// Change to the given directory.
+    Path(PathBuf),
+}
+
+impl WorkingDirectory {
+    fn resolve<'a>(&'a self, script_path: Option<&'a Path>) -> Option<&'a Path> {
+        match self {
+            Self::Keep => None,
+            Self::ScriptDir => script_path
+                .and_then(Path::parent)
+                .filter(|dir| !dir.as_os_str().is_empty()),
+            Self::Path(dir) => Some(dir),
+        }
+    }
+}
+
+/// Changes the working directory of the process and restores it when dropped.
+struct WorkingDirectoryGuard(Option<PathBuf>);
+
+impl WorkingDirectoryGuard {
+    fn enter(dir: Option<&Path>) -> ScriptResult<Self> {
+        let Some(dir) = dir else {
+            return Ok(Self(None));
+        };
+        let previous = env::current_dir().map_err(ScriptError::WorkingDirectory)?;
+        env::set_current_dir(dir).map_err(ScriptError::WorkingDirectory)?;
+        Ok(Self(Some(previous)))
+    }
+}
+
+impl Drop for WorkingDirectoryGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = &self.0 {
+            let _ = env::set_current_dir(previous);
+        }
+    }
 }
 
 /// A wrapper for the `VSScript` environment.
 #[derive(Debug)]
 pub struct Environment {
     handle: NonNull<ffi::VSScript>,
+    working_directory: WorkingDirectory,
 }
 
 unsafe impl Send for Environment {}
@@ -39,6 +94,7 @@ impl Drop for Environment {
     #[inline]
     fn drop(&mut self) {
         unsafe {
+            crate::plugin::hand_over_registered_functions(&self.get_core());
             ScriptAPI::get_cached().free_script(self.handle.as_ptr());
         }
     }
@@ -69,6 +125,7 @@ impl Environment {
         let handle = unsafe { api.create_script(core.as_ptr()) };
         let environment = Self {
             handle: unsafe { NonNull::new_unchecked(handle) },
+            working_directory: WorkingDirectory::Keep,
         };
 
         match unsafe { environment.error() } {
@@ -79,7 +136,7 @@ impl Environment {
 
     /// Calls `ScriptAPI::eval_buffer()`.
     fn evaluate_script(&self, args: EvaluateScriptArgs) -> ScriptResult<()> {
-        let (script, path) = match args {
+        let (script, script_path) = match args {
             EvaluateScriptArgs::Script(script) => (script.to_owned(), None),
             EvaluateScriptArgs::File(path) => {
                 let mut file = File::open(path).map_err(ScriptError::FileOpen)?;
@@ -87,16 +144,36 @@ impl Environment {
                 file.read_to_string(&mut script)
                     .map_err(ScriptError::FileRead)?;
 
-                // vsscript throws an error if it's not valid UTF-8 anyway.
-                let path = path.to_str().ok_or(ScriptError::PathInvalidUnicode)?;
-                let path = CString::new(path)?;
-
                 (script, Some(path))
             }
+            EvaluateScriptArgs::Named(script, path) => (script.to_owned(), Some(path)),
         };
 
+        // The path becomes `__file__`, which has to stay valid after changing the directory.
+        let script_path = script_path
+            .map(|path| {
+                if path.is_relative() && self.working_directory.resolve(Some(path)).is_some() {
+                    path::absolute(path)
+                        .map(Cow::Owned)
+                        .map_err(ScriptError::WorkingDirectory)
+                } else {
+                    Ok(Cow::Borrowed(path))
+                }
+            })
+            .transpose()?;
+        let script_path = script_path.as_deref();
+
+        // vsscript throws an error if it's not valid UTF-8 anyway.
+        let path = script_path
+            .map(|path| {
+                let path = path.to_str().ok_or(ScriptError::PathInvalidUnicode)?;
+                Ok::<_, ScriptError>(CString::new(path)?)
+            })
+            .transpose()?;
+
         let script = CString::new(script)?;
 
+        let _guard = WorkingDirectoryGuard::enter(self.working_directory.resolve(script_path))?;
         let rv = unsafe {
             ScriptAPI::get_cached().eval_buffer(
                 self.handle.as_ptr(),
@@ -134,6 +211,42 @@ impl Environment {
         self.evaluate_script(EvaluateScriptArgs::Script(script))
     }
 
+    /// Evaluates a script contained in a string as if it was read from `virtual_path`.
+    ///
+    /// The path becomes `__file__` and appears in tracebacks, the file doesn't need to exist.
+    #[inline]
+    pub fn eval_script_as<P: AsRef<Path>>(
+        &mut self,
+        script: &str,
+        virtual_path: P,
+    ) -> ScriptResult<()> {
+        self.evaluate_script(EvaluateScriptArgs::Named(script, virtual_path.as_ref()))
+    }
+
+    /// Reads a script from `reader`, such as standard input, and evaluates it as if it was read
+    /// from `virtual_path`.
+    pub fn eval_reader<R: Read, P: AsRef<Path>>(
+        &mut self,
+        mut reader: R,
+        virtual_path: P,
+    ) -> ScriptResult<()> {
+        let mut script = String::new();
+        reader
+            .read_to_string(&mut script)
+            .map_err(ScriptError::FileRead)?;
+        self.eval_script_as(&script, virtual_path)
+    }
+
+    /// Sets the working directory used while evaluating scripts. [`WorkingDirectory::Keep`] by
+    /// default.
+    ///
+    /// The working directory belongs to the whole process, so other threads see the change while a
+    /// script is evaluated.
+    #[inline]
+    pub fn set_working_directory(&mut self, working_directory: WorkingDirectory) {
+        self.working_directory = working_directory;
+    }
+
     /// Evaluates a script contained in a file.
     #[inline]
     pub fn eval_file<P: AsRef<Path>>(&mut self, path: P) -> ScriptResult<()> {
@@ -165,6 +278,48 @@ impl Environment {
         }
     }
 
+    /// Retrieves a variable from the script environment as a typed value.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rustsynth::core::{CoreCreationFlags, CoreRef};
+    /// use rustsynth::vsscript::Environment;
+    ///
+    /// let core = CoreRef::new(CoreCreationFlags::NONE);
+    /// let env = Environment::from_script(&core, "radius = 3").unwrap();
+    /// assert_eq!(env.get_var::<i64>("radius").unwrap(), 3);
+    /// ```
+    pub fn get_var<'env, T: FunctionValue<'env>>(&'env self, name: &str) -> ScriptResult<T> {
+        let mut map = Map::new()?;
+        self.get_variable(name, &mut map)?;
+        Ok(T::get_from_map(&map, name)?)
+    }
+
+    /// Sets a single variable in the script environment.
+    pub fn set_var<'env, T: FunctionValue<'env>>(
+        &'env self,
+        name: &str,
+        value: T,
+    ) -> ScriptResult<()> {
+        let mut map = Map::new()?;
+        T::store_in_map(&mut map, name, &value)?;
+        self.set_variables(&map)
+    }
+
+    /// Sets every key of `vars` as a variable in the script environment.
+    pub fn set_vars<'env, T: IntoOwnedMap<'env>>(&'env self, vars: T) -> ScriptResult<()> {
+        self.set_variables(&vars.into_owned_map()?)
+    }
+
+    /// Sets the fields of `vars` as variables in the script environment.
+    ///
+    /// See [`to_map`](crate::map::to_map) for how values are represented.
+    #[cfg(feature = "serde")]
+    #[doc(cfg(feature = "serde"))]
+    pub fn set_serialized_vars<T: serde::Serialize + ?Sized>(&self, vars: &T) -> ScriptResult<()> {
+        self.set_variables(&crate::map::to_map(vars)?)
+    }
+
     /// Retrieves a node from the script environment. A node in the script must have been marked for output with the requested index.
     ///
     /// Returns [None] if there is no node at the requested index.
@@ -201,6 +356,8 @@ impl Environment {
     }
 
     /// Set whether or not the working directory is temporarily changed to the same location as the script file when evaluateFile is called. Off by default.
+    ///
+    /// See [`Environment::set_working_directory()`] for a typed alternative.
     pub fn eval_set_working_dir(&self, set_cwd: i32) {
         unsafe { ScriptAPI::get_cached().eval_set_working_dir(self.handle.as_ptr(), set_cwd) };
     }
@@ -241,4 +398,35 @@ impl Environment {
         };
         (list, ret)
     }
+
+    #[cfg(feature = "script-api-42")]
+    #[doc(cfg(feature = "script-api-42"))]
+    /// Retrieves every output the script has set, in order of their index.
+    #[must_use]
+    pub fn outputs(&'_ self) -> Vec<ScriptOutput<'_>> {
+        let api = unsafe { ScriptAPI::get_cached() };
+        let count = api.get_available_output_nodes(self.handle.as_ptr(), 0, ptr::null_mut());
+        let mut indices = vec![0; count.max(0) as usize];
+        let count = api.get_available_output_nodes(
+            self.handle.as_ptr(),
+            indices.len() as i32,
+            indices.as_mut_ptr(),
+        );
+        indices.truncate(count.clamp(0, indices.len() as i32) as usize);
+        indices.sort_unstable();
+
+        indices
+            .into_iter()
+            .filter_map(|index| {
+                let node = self.get_output(index)?;
+                Some(ScriptOutput {
+                    index,
+                    media_type: node.media_type(),
+                    node,
+                    alpha: self.get_output_alpha(index),
+                    alt_output_mode: self.get_alt_output_mode(index).into(),
+                })
+            })
+            .collect()
+    }
 }
@@ -1,8 +1,12 @@
+use std::borrow::Cow;
 use std::ffi::{CString, NulError};
 use std::{fmt, io};
 
 use thiserror::Error;
 
+use crate::map::MapError;
+use crate::vsscript::Traceback;
+
 /// The error type for `vsscript` operations.
 #[derive(Error, Debug)]
 pub enum ScriptError {
@@ -12,6 +16,8 @@ pub enum ScriptError {
     FileOpen(#[source] io::Error),
     #[error("Couldn't read the file")]
     FileRead(#[source] io::Error),
+    #[error("Couldn't change the working directory")]
+    WorkingDirectory(#[source] io::Error),
     #[error("Path isn't valid Unicode")]
     PathInvalidUnicode,
     #[error("An error occurred in VSScript")]
@@ -24,6 +30,8 @@ pub enum ScriptError {
     NoOutput,
     #[error("Couldn't get the VapourSynth API")]
     NoAPI,
+    #[error("Couldn't convert the variable")]
+    Map(#[source] MapError),
 }
 
 impl From<NulError> for ScriptError {
@@ -33,6 +41,13 @@ impl From<NulError> for ScriptError {
     }
 }
 
+impl From<MapError> for ScriptError {
+    #[inline]
+    fn from(x: MapError) -> Self {
+        Self::Map(x)
+    }
+}
+
 impl From<VSScriptError> for ScriptError {
     #[inline]
     fn from(x: VSScriptError) -> Self {
@@ -40,6 +55,17 @@ impl From<VSScriptError> for ScriptError {
     }
 }
 
+impl ScriptError {
+    /// The Python exception, if this is an error raised by the script.
+    #[must_use]
+    pub fn traceback(&self) -> Option<Traceback> {
+        match self {
+            Self::VSScript(error) => error.traceback(),
+            _ => None,
+        }
+    }
+}
+
 pub type ScriptResult<T> = std::result::Result<T, ScriptError>;
 
 /// A container for a `VSScript` error.
@@ -59,4 +85,17 @@ impl VSScriptError {
     pub(crate) const fn new(message: CString) -> Self {
         Self(message)
     }
+
+    /// The error text as `VSScript` reported it, usually a full Python traceback.
+    #[inline]
+    #[must_use]
+    pub fn raw(&self) -> Cow<'_, str> {
+        self.0.to_string_lossy()
+    }
+
+    /// Parses the error text into the exception type, message and stack frames.
+    #[must_use]
+    pub fn traceback(&self) -> Option<Traceback> {
+        Traceback::parse(&self.raw())
+    }
 }
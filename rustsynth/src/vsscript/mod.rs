@@ -156,10 +156,22 @@ impl ScriptAPI {
 }
 
 mod errors;
-pub use self::errors::{ScriptError, VSScriptError};
+pub use self::errors::{ScriptError, ScriptResult, VSScriptError};
 
 mod environment;
-pub use self::environment::Environment;
+pub use self::environment::{Environment, WorkingDirectory};
+
+mod pool;
+pub use self::pool::{EnvironmentPool, JobReport};
+
+mod output;
+pub use self::output::{AltOutputMode, ScriptOutput};
+
+mod traceback;
+pub use self::traceback::{Traceback, TracebackFrame};
+
+mod watcher;
+pub use self::watcher::ScriptWatcher;
 
 #[cfg(test)]
 pub mod tests;
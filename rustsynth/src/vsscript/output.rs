@@ -0,0 +1,54 @@
+use crate::format::MediaType;
+use crate::node::Node;
+
+/// The `alt_output` value a script passed to `set_output()`.
+///
+/// The value has no fixed meaning, in vspipe and vsvfw it selects an alternate packing of the
+/// output format when several are available.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum AltOutputMode {
+    /// No alternate output was requested.
+    Default,
+    /// The first alternate output format.
+    Alternate,
+    /// Any other value.
+    Other(i32),
+}
+
+impl From<i32> for AltOutputMode {
+    #[inline]
+    fn from(mode: i32) -> Self {
+        match mode {
+            0 => Self::Default,
+            1 => Self::Alternate,
+            x => Self::Other(x),
+        }
+    }
+}
+
+impl From<AltOutputMode> for i32 {
+    #[inline]
+    fn from(mode: AltOutputMode) -> Self {
+        match mode {
+            AltOutputMode::Default => 0,
+            AltOutputMode::Alternate => 1,
+            AltOutputMode::Other(x) => x,
+        }
+    }
+}
+
+/// A node the script marked for output, as returned by
+/// [`Environment::outputs()`](crate::vsscript::Environment::outputs).
+#[derive(Debug, Clone)]
+pub struct ScriptOutput<'core> {
+    /// The index passed to `set_output()`.
+    pub index: i32,
+    /// The output node.
+    pub node: Node<'core>,
+    /// The alpha node set alongside a video output.
+    pub alpha: Option<Node<'core>>,
+    /// The alternate output mode.
+    pub alt_output_mode: AltOutputMode,
+    /// Whether the output is video or audio.
+    pub media_type: MediaType,
+}
@@ -0,0 +1,163 @@
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::core::CoreBuilder;
+use crate::vsscript::errors::ScriptResult;
+use crate::vsscript::{Environment, ScriptError, WorkingDirectory};
+
+type Setup = Box<dyn Fn(&Environment, &Path) -> ScriptResult<()> + Send + Sync>;
+
+/// The outcome of one script run by an [`EnvironmentPool`].
+#[derive(Debug)]
+pub struct JobReport<T> {
+    /// The position of the script in the submitted list.
+    pub index: usize,
+    /// The script path.
+    pub path: PathBuf,
+    /// The value returned by the handler, or the error that stopped the script.
+    pub result: Result<T, ScriptError>,
+    /// The code passed to `sys.exit()` by the script, 0 if it didn't call it.
+    pub exit_code: i32,
+}
+
+/// Evaluates many scripts in parallel, each in its own environment.
+///
+/// At most [`size()`](Self::size) environments exist at once, one per worker thread. Every
+/// script gets a fresh environment on a fresh core built from the [`CoreBuilder`], which is freed
+/// before the worker takes the next script. Reusing an environment would leak Python globals and outputs from one
+/// script into the next, and `VSScript` frees the core together with its environment.
+///
+/// # Example
+/// ```no_run
+/// use rustsynth::core::CoreBuilder;
+/// use rustsynth::vsscript::EnvironmentPool;
+///
+/// let pool = EnvironmentPool::new(4)
+///     .with_core_builder(CoreBuilder::new().with_thread_count(2).with_max_cache_size(1 << 30));
+/// let reports = pool.run(["a.vpy", "b.vpy"], |environment| {
+///     environment.get_output(0).and_then(|node| node.video_info()).map(|info| info.num_frames)
+/// });
+/// for report in reports {
+///     match report.result {
+///         Ok(frames) => println!("{}: {:?} frames", report.path.display(), frames),
+///         Err(e) => eprintln!("{}: {} (exit code {})", report.path.display(), e, report.exit_code),
+///     }
+/// }
+/// ```
+pub struct EnvironmentPool {
+    size: NonZeroUsize,
+    core_builder: CoreBuilder,
+    working_directory: WorkingDirectory,
+    setup: Option<Setup>,
+}
+
+impl EnvironmentPool {
+    /// Creates a pool running up to `size` scripts at once, at least one.
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        Self {
+            size: NonZeroUsize::new(size).unwrap_or(NonZeroUsize::MIN),
+            core_builder: CoreBuilder::new(),
+            working_directory: WorkingDirectory::Keep,
+            setup: None,
+        }
+    }
+
+    /// Sets the builder of the core each script runs on.
+    ///
+    /// Every worker has its own core, so thread counts and cache sizes add up across the pool.
+    #[must_use]
+    pub fn with_core_builder(mut self, core_builder: CoreBuilder) -> Self {
+        self.core_builder = core_builder;
+        self
+    }
+
+    /// Sets the working directory policy of the environments.
+    ///
+    /// The working directory belongs to the whole process, so anything but
+    /// [`WorkingDirectory::Keep`] limits the pool to one script at a time.
+    #[must_use]
+    pub fn with_working_directory(mut self, working_directory: WorkingDirectory) -> Self {
+        if working_directory != WorkingDirectory::Keep {
+            self.size = NonZeroUsize::MIN;
+        }
+        self.working_directory = working_directory;
+        self
+    }
+
+    /// Runs `setup` on each fresh environment before its script is evaluated, for example to set
+    /// variables.
+    #[must_use]
+    pub fn with_setup<F>(mut self, setup: F) -> Self
+    where
+        F: Fn(&Environment, &Path) -> ScriptResult<()> + Send + Sync + 'static,
+    {
+        self.setup = Some(Box::new(setup));
+        self
+    }
+
+    /// The maximum number of scripts evaluated at once.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size.get()
+    }
+
+    /// Evaluates every script and calls `handler` with its environment, from which it can take
+    /// the output nodes. The environment is freed once the handler returns.
+    ///
+    /// Returns a report per script in the order they were given.
+    pub fn run<I, P, F, T>(&self, scripts: I, handler: F) -> Vec<JobReport<T>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+        F: Fn(&Environment) -> T + Sync,
+        T: Send,
+    {
+        let queue: Vec<PathBuf> = scripts
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect();
+        let jobs = Mutex::new(queue.into_iter().enumerate());
+        let reports = Mutex::new(Vec::new());
+
+        thread::scope(|scope| {
+            for _ in 0..self.size.get() {
+                scope.spawn(|| loop {
+                    let Some((index, path)) = jobs.lock().unwrap().next() else {
+                        break;
+                    };
+                    let report = self.run_job(index, path, &handler);
+                    reports.lock().unwrap().push(report);
+                });
+            }
+        });
+
+        let mut reports = reports.into_inner().unwrap();
+        reports.sort_by_key(|report| report.index);
+        reports
+    }
+
+    fn run_job<F, T>(&self, index: usize, path: PathBuf, handler: &F) -> JobReport<T>
+    where
+        F: Fn(&Environment) -> T,
+    {
+        let mut exit_code = 0;
+        let result = Environment::new(&self.core_builder.clone().build()).and_then(|mut env| {
+            env.set_working_directory(self.working_directory.clone());
+            if let Some(setup) = &self.setup {
+                setup(&env, &path)?;
+            }
+            let evaluated = env.eval_file(&path);
+            exit_code = env.get_exit_code();
+            evaluated.map(|()| handler(&env))
+        });
+        JobReport {
+            index,
+            path,
+            result,
+            exit_code,
+        }
+    }
+}
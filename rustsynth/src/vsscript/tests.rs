@@ -5,4 +5,313 @@ mod tests {
         let version = vsapi.get_api_version();
         assert!(version >= crate::ffi::VSSCRIPT_API_VERSION)
     }
+
+    #[test]
+    fn test_alt_output_mode() {
+        use crate::vsscript::AltOutputMode;
+
+        assert_eq!(AltOutputMode::from(0), AltOutputMode::Default);
+        assert_eq!(AltOutputMode::from(1), AltOutputMode::Alternate);
+        assert_eq!(AltOutputMode::from(2), AltOutputMode::Other(2));
+        for mode in [0, 1, 2, -1] {
+            assert_eq!(i32::from(AltOutputMode::from(mode)), mode);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "script-api-42")]
+    fn test_outputs() {
+        use crate::core::{CoreCreationFlags, CoreRef};
+        use crate::format::MediaType;
+        use crate::vsscript::{AltOutputMode, Environment};
+
+        let core = CoreRef::new(CoreCreationFlags::NONE);
+        let environment = Environment::from_script(
+            &core,
+            "import vapoursynth as vs\n\
+             c = vs.core.std.BlankClip()\n\
+             c.set_output(3, alt_output=1)\n\
+             c.set_output(0, alpha=c)\n\
+             vs.core.std.BlankAudio().set_output(1)\n",
+        )
+        .unwrap();
+
+        let outputs = environment.outputs();
+        let indices: Vec<_> = outputs.iter().map(|output| output.index).collect();
+        assert_eq!(indices, [0, 1, 3]);
+
+        assert_eq!(outputs[0].media_type, MediaType::Video);
+        assert!(outputs[0].alpha.is_some());
+        assert_eq!(outputs[0].alt_output_mode, AltOutputMode::Default);
+        assert_eq!(outputs[1].media_type, MediaType::Audio);
+        assert!(outputs[1].alpha.is_none());
+        assert_eq!(outputs[2].alt_output_mode, AltOutputMode::Alternate);
+    }
+
+    #[test]
+    fn test_typed_variables() {
+        use crate::core::{CoreCreationFlags, CoreRef};
+        use crate::vsscript::{Environment, ScriptError};
+
+        let core = CoreRef::new(CoreCreationFlags::NONE);
+        let mut environment = Environment::new(&core).unwrap();
+        environment.set_var("radius", 3i64).unwrap();
+        environment.set_var("label", "clip".to_string()).unwrap();
+        environment
+            .eval_script("doubled = radius * 2\nlabel_len = float(len(label))")
+            .unwrap();
+
+        assert_eq!(environment.get_var::<i64>("doubled").unwrap(), 6);
+        assert_eq!(environment.get_var::<f64>("label_len").unwrap(), 4.0);
+        assert_eq!(environment.get_var::<String>("label").unwrap(), "clip");
+        assert!(matches!(
+            environment.get_var::<i64>("missing"),
+            Err(ScriptError::NoSuchVariable)
+        ));
+        assert!(matches!(
+            environment.get_var::<f64>("doubled"),
+            Err(ScriptError::Map(_))
+        ));
+    }
+
+    #[test]
+    fn test_traceback_parse() {
+        use crate::vsscript::{Traceback, TracebackFrame};
+
+        let text = "Python exception: No attribute with the name foo exists. Did you mistype a plugin namespace or forget to install a plugin?\n\n\
+Traceback (most recent call last):\n  \
+  File \"src/cython/vapoursynth.pyx\", line 3365, in vapoursynth._vpy_evaluate\n  \
+  File \"script.vpy\", line 3, in <module>\n    \
+    clip = core.foo.Bar(clip)\n           \
+           ^^^^^^^^\n  \
+  File \"src/cython/vapoursynth.pyx\", line 2100, in vapoursynth.Core.__getattr__\n\
+AttributeError: No attribute with the name foo exists. Did you mistype a plugin namespace or forget to install a plugin?\n";
+
+        let traceback = Traceback::parse(text).unwrap();
+        assert_eq!(traceback.exception, "AttributeError");
+        assert!(traceback
+            .message
+            .starts_with("No attribute with the name foo"));
+        assert_eq!(traceback.frames.len(), 3);
+        assert_eq!(
+            traceback.frame_in("script.vpy"),
+            Some(&TracebackFrame {
+                file: "script.vpy".to_owned(),
+                line: 3,
+                function: Some("<module>".to_owned()),
+                code: Some("clip = core.foo.Bar(clip)".to_owned()),
+            })
+        );
+        assert_eq!(traceback.frames[2].code, None);
+    }
+
+    #[test]
+    fn test_traceback_parse_syntax_error() {
+        use crate::vsscript::Traceback;
+
+        let text = "Python exception: invalid syntax (script.vpy, line 2)\n\n\
+Traceback (most recent call last):\n  \
+  File \"src/cython/vapoursynth.pyx\", line 3365, in vapoursynth._vpy_evaluate\n  \
+  File \"script.vpy\", line 2\n    \
+    x = = 1\n        \
+        ^\n\
+SyntaxError: invalid syntax\n";
+
+        let traceback = Traceback::parse(text).unwrap();
+        assert_eq!(traceback.exception, "SyntaxError");
+        assert_eq!(traceback.message, "invalid syntax");
+        let frame = traceback.frame_in("script.vpy").unwrap();
+        assert_eq!(frame.line, 2);
+        assert_eq!(frame.function, None);
+        assert_eq!(frame.code.as_deref(), Some("x = = 1"));
+    }
+
+    #[test]
+    fn test_traceback_parse_chained() {
+        use crate::vsscript::Traceback;
+
+        let text = "Traceback (most recent call last):\n  \
+  File \"a.py\", line 1, in <module>\n\
+KeyError: 'x'\n\n\
+During handling of the above exception, another exception occurred:\n\n\
+Traceback (most recent call last):\n  \
+  File \"b.py\", line 7, in handler\n\
+vapoursynth.Error: first line\nsecond line\n";
+
+        let traceback = Traceback::parse(text).unwrap();
+        assert_eq!(traceback.exception, "vapoursynth.Error");
+        assert_eq!(traceback.message, "first line\nsecond line");
+        assert_eq!(traceback.frames.len(), 1);
+        assert_eq!(traceback.frames[0].file, "b.py");
+
+        assert_eq!(Traceback::parse("Python exception: something"), None);
+    }
+
+    #[test]
+    fn test_script_watcher() {
+        use crate::vsscript::ScriptWatcher;
+        use std::fs::{self, File};
+        use std::time::{Duration, SystemTime};
+
+        let dir = std::env::temp_dir().join(format!("rustsynth-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.vpy");
+        let module = dir.join("rustsynth_watch_helper.py");
+        fs::write(&module, "value = 1\n").unwrap();
+        // An installed package next to the script, as in a virtual environment
+        let packages = dir.join("venv").join("site-packages");
+        fs::create_dir_all(&packages).unwrap();
+        fs::write(
+            packages.join("rustsynth_watch_installed.py"),
+            "import sys\nsys.rustsynth_installed_loads = getattr(sys, 'rustsynth_installed_loads', 0) + 1\n",
+        )
+        .unwrap();
+        fs::write(
+            &script,
+            "import os, sys\nsys.path.insert(0, os.path.dirname(__file__))\nsys.path.insert(0, os.path.join(os.path.dirname(__file__), 'venv', 'site-packages'))\nimport rustsynth_watch_helper, rustsynth_watch_installed\nvalue = rustsynth_watch_helper.value\nloads = sys.rustsynth_installed_loads\n",
+        )
+        .unwrap();
+        let touch = |path: &std::path::Path, secs| {
+            File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        };
+
+        let mut watcher = ScriptWatcher::new(&script);
+        let env = watcher.poll().unwrap().unwrap();
+        assert_eq!(env.get_var::<i64>("value").unwrap(), 1);
+        assert!(watcher.poll().is_none());
+        assert!(watcher
+            .watched_files()
+            .any(|path| path.ends_with("rustsynth_watch_helper.py")));
+
+        fs::write(&module, "value = 2\n").unwrap();
+        touch(&module, 1_000_000);
+        let env = watcher.poll().unwrap().unwrap();
+        assert_eq!(env.get_var::<i64>("value").unwrap(), 2);
+        // Installed modules are neither watched nor imported again
+        assert_eq!(env.get_var::<i64>("loads").unwrap(), 1);
+        assert!(!watcher
+            .watched_files()
+            .any(|path| path.ends_with("rustsynth_watch_installed.py")));
+
+        fs::write(&script, "value = undefined_name\n").unwrap();
+        touch(&script, 2_000_000);
+        let error = watcher.poll().unwrap().unwrap_err();
+        assert_eq!(error.traceback().unwrap().exception, "NameError");
+        let env = watcher.environment().unwrap();
+        assert_eq!(env.get_var::<i64>("value").unwrap(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_eval_script_as() {
+        use crate::core::{CoreCreationFlags, CoreRef};
+        use crate::vsscript::{Environment, WorkingDirectory};
+
+        let dir = std::env::temp_dir().canonicalize().unwrap();
+        let before = std::env::current_dir().unwrap();
+        let core = CoreRef::new(CoreCreationFlags::NONE);
+        let mut environment = Environment::new(&core).unwrap();
+        environment.set_working_directory(WorkingDirectory::ScriptDir);
+        environment
+            .eval_script_as(
+                "import os\nname = __file__\ncwd = os.getcwd()\n",
+                dir.join("virtual.vpy"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            environment.get_var::<String>("name").unwrap(),
+            dir.join("virtual.vpy").to_str().unwrap()
+        );
+        assert_eq!(
+            environment.get_var::<String>("cwd").unwrap(),
+            dir.to_str().unwrap()
+        );
+        assert_eq!(std::env::current_dir().unwrap(), before);
+
+        // A relative path is made absolute before changing into its directory
+        let relative =
+            std::path::PathBuf::from(format!("rustsynth-script-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&relative).unwrap();
+        std::fs::write(
+            relative.join("script.vpy"),
+            "import os\nname = __file__\nfound = int(os.path.isfile(__file__))\ncwd = os.getcwd()\n",
+        )
+        .unwrap();
+        let result = environment.eval_file(relative.join("script.vpy"));
+        std::fs::remove_dir_all(&relative).unwrap();
+        result.unwrap();
+
+        assert_eq!(
+            environment.get_var::<String>("name").unwrap(),
+            before.join(&relative).join("script.vpy").to_str().unwrap()
+        );
+        assert_eq!(environment.get_var::<i64>("found").unwrap(), 1);
+        assert_eq!(
+            environment.get_var::<String>("cwd").unwrap(),
+            before.join(&relative).to_str().unwrap()
+        );
+        assert_eq!(std::env::current_dir().unwrap(), before);
+
+        environment
+            .eval_reader("stdin_value = 5\n".as_bytes(), "<stdin>")
+            .unwrap();
+        assert_eq!(environment.get_var::<i64>("stdin_value").unwrap(), 5);
+    }
+
+    #[test]
+    fn test_environment_pool() {
+        use crate::core::CoreBuilder;
+        use crate::vsscript::{EnvironmentPool, WorkingDirectory};
+        use std::fs;
+
+        let dir = std::env::temp_dir().join(format!("rustsynth-pool-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let scripts = [
+            (
+                "ok.vpy",
+                "import vapoursynth as vs\nvs.core.std.BlankClip(length=7).set_output()\n",
+            ),
+            ("error.vpy", "raise ValueError('bad job')\n"),
+            ("exit.vpy", "import sys\nsys.exit(3)\n"),
+        ]
+        .map(|(name, source)| {
+            let path = dir.join(name);
+            fs::write(&path, source).unwrap();
+            path
+        });
+
+        let pool =
+            EnvironmentPool::new(2).with_core_builder(CoreBuilder::new().with_thread_count(1));
+        let reports = pool.run(&scripts, |environment| {
+            environment
+                .get_output(0)
+                .unwrap()
+                .video_info()
+                .unwrap()
+                .num_frames
+        });
+
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].path, scripts[0]);
+        assert_eq!(reports[0].result.as_ref().unwrap(), &7);
+        let error = reports[1].result.as_ref().unwrap_err();
+        assert_eq!(error.traceback().unwrap().exception, "ValueError");
+        assert!(reports[2].result.is_err());
+        assert_eq!(reports[2].exit_code, 3);
+
+        // Changing the working directory of the process can't be done from several workers
+        let pool = EnvironmentPool::new(2).with_working_directory(WorkingDirectory::ScriptDir);
+        assert_eq!(pool.size(), 1);
+        let pool = EnvironmentPool::new(2).with_working_directory(WorkingDirectory::Keep);
+        assert_eq!(pool.size(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,93 @@
+/// A Python exception raised while evaluating a script, parsed from the `VSScript` error text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traceback {
+    /// The exception type, such as `NameError` or `vapoursynth.Error`.
+    pub exception: String,
+    /// The exception message, empty if it had none.
+    pub message: String,
+    /// The stack frames, outermost first.
+    pub frames: Vec<TracebackFrame>,
+}
+
+/// A stack frame of a [`Traceback`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracebackFrame {
+    /// The file the code is in.
+    pub file: String,
+    /// The line number, starting at 1.
+    pub line: u32,
+    /// The function, `<module>` for the top level of a file, `None` for syntax errors.
+    pub function: Option<String>,
+    /// The source line, if Python printed it.
+    pub code: Option<String>,
+}
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+
+impl Traceback {
+    /// Parses the text of a Python traceback.
+    ///
+    /// With chained exceptions only the last traceback is kept. Returns `None` if the text has no
+    /// traceback or no exception line after it.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let start = text.rfind(TRACEBACK_HEADER)? + TRACEBACK_HEADER.len();
+        let mut lines = text[start..].lines().skip(1).peekable();
+
+        let mut frames: Vec<TracebackFrame> = Vec::new();
+        while let Some(line) = lines.next_if(|line| line.starts_with(' ')) {
+            let line = line.trim();
+            if let Some(frame) = parse_frame(line) {
+                frames.push(frame);
+            } else if let Some(frame) = frames.last_mut() {
+                // The source line, or the caret marking the column of a syntax error
+                if frame.code.is_none() && !line.chars().all(|c| matches!(c, '^' | '~' | ' ')) {
+                    frame.code = Some(line.to_owned());
+                }
+            }
+        }
+
+        let line = lines.next()?;
+        let (exception, message) = line.split_once(": ").unwrap_or((line, ""));
+        if exception.is_empty()
+            || !exception
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            return None;
+        }
+        let message = std::iter::once(message)
+            .chain(lines)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(Self {
+            exception: exception.to_owned(),
+            message: message.trim_end().to_owned(),
+            frames,
+        })
+    }
+
+    /// The innermost frame in `file`, which is where the error happened as far as that file is
+    /// concerned.
+    #[must_use]
+    pub fn frame_in(&self, file: &str) -> Option<&TracebackFrame> {
+        self.frames.iter().rev().find(|frame| frame.file == file)
+    }
+}
+
+/// Parses `File "path", line 3, in function`, the function being absent for syntax errors.
+fn parse_frame(line: &str) -> Option<TracebackFrame> {
+    let rest = line.strip_prefix("File \"")?;
+    let (file, rest) = rest.split_once("\", line ")?;
+    let (line, function) = match rest.split_once(", in ") {
+        Some((line, function)) => (line, Some(function.to_owned())),
+        None => (rest, None),
+    };
+    Some(TracebackFrame {
+        file: file.to_owned(),
+        line: line.parse().ok()?,
+        function,
+        code: None,
+    })
+}
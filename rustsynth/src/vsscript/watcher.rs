@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::core::{CoreCreationFlags, CoreRef};
+use crate::vsscript::errors::ScriptResult;
+use crate::vsscript::Environment;
+
+/// Lists the files of the modules the script imported from its directory, optionally removing
+/// them from `sys.modules` first so the next evaluation imports them again.
+///
+/// Every environment shares the same interpreter, and with it the module cache. Modules already
+/// loaded before the first evaluation, and those installed under the interpreter's prefix or a
+/// `site-packages` directory, are left alone even when a virtual environment lives next to the
+/// script.
+fn local_modules_snippet(purge: bool) -> String {
+    format!(
+        "def __rustsynth_local_modules():
+    import os, site, sys
+    def under(path, dirs):
+        return any(path.startswith(os.path.join(os.path.abspath(d), '')) for d in dirs)
+    root = os.path.abspath(__rustsynth_root)
+    installed = [sys.prefix, sys.base_prefix, sys.exec_prefix, sys.base_exec_prefix]
+    installed += getattr(site, 'getsitepackages', list)()
+    installed.append(site.getusersitepackages())
+    installed = [d for d in installed if d and not under(root, [d])]
+    preloaded = set(__rustsynth_preloaded.split('\\n'))
+    files = []
+    for name, module in list(sys.modules.items()):
+        path = getattr(module, '__file__', None)
+        if name in preloaded or not path:
+            continue
+        path = os.path.abspath(path)
+        parts = path.split(os.sep)
+        if not under(path, [root]) or under(path, installed) \\
+                or 'site-packages' in parts or 'dist-packages' in parts:
+            continue
+        files.append(path)
+        if {purge}:
+            del sys.modules[name]
+    return '\\n'.join(files)
+__rustsynth_modules = __rustsynth_local_modules()
+del __rustsynth_local_modules
+",
+        purge = if purge { "True" } else { "False" }
+    )
+}
+
+type Setup = Box<dyn Fn(&Environment) -> ScriptResult<()> + Send + Sync>;
+
+/// Re-evaluates a script whenever it, or a module it imports from its directory, changes.
+///
+/// Changes are found by polling modification times, so no platform-specific API is needed. Each
+/// reload evaluates into a fresh [`Environment`] with a new core. When it fails the last
+/// environment that evaluated successfully is kept, so its outputs stay usable.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use rustsynth::vsscript::ScriptWatcher;
+///
+/// let mut watcher = ScriptWatcher::new("script.vpy");
+/// loop {
+///     match watcher.wait(Duration::from_millis(500)) {
+///         Ok(environment) => println!("{} outputs", environment.outputs().len()),
+///         Err(error) => eprintln!("{}", error.traceback().map_or(error.to_string(), |t| t.message)),
+///     }
+/// }
+/// ```
+pub struct ScriptWatcher {
+    path: PathBuf,
+    flags: CoreCreationFlags,
+    setup: Option<Setup>,
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    environment: Option<Environment>,
+    loaded: bool,
+    /// The names in `sys.modules` before the first evaluation, one per line.
+    preloaded: Option<String>,
+}
+
+impl ScriptWatcher {
+    /// Creates a watcher for the script at `path`. Nothing is evaluated until the first
+    /// [`poll()`](Self::poll) or [`wait()`](Self::wait).
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        Self {
+            files: vec![(path.clone(), None)],
+            path,
+            flags: CoreCreationFlags::NONE,
+            setup: None,
+            environment: None,
+            loaded: false,
+            preloaded: None,
+        }
+    }
+
+    /// Sets the flags of the core created for each evaluation.
+    #[must_use]
+    pub fn with_core_flags(mut self, flags: CoreCreationFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Runs `setup` on each fresh environment before the script is evaluated, for example to set
+    /// variables.
+    #[must_use]
+    pub fn with_setup<F>(mut self, setup: F) -> Self
+    where
+        F: Fn(&Environment) -> ScriptResult<()> + Send + Sync + 'static,
+    {
+        self.setup = Some(Box::new(setup));
+        self
+    }
+
+    /// The script path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The files whose changes trigger a reload: the script and the local modules it imported.
+    pub fn watched_files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// The environment of the last successful evaluation.
+    #[must_use]
+    pub fn environment(&self) -> Option<&Environment> {
+        self.environment.as_ref()
+    }
+
+    /// Reloads the script if this is the first call or a watched file changed since the last
+    /// evaluation, and returns `None` otherwise.
+    pub fn poll(&mut self) -> Option<ScriptResult<&Environment>> {
+        if self.loaded && !self.changed() {
+            return None;
+        }
+        Some(self.reload())
+    }
+
+    /// Blocks until [`poll()`](Self::poll) reloads the script, checking every `interval`.
+    pub fn wait(&mut self, interval: Duration) -> ScriptResult<&Environment> {
+        while self.loaded && !self.changed() {
+            thread::sleep(interval);
+        }
+        self.reload()
+    }
+
+    /// Evaluates the script into a fresh environment, regardless of whether anything changed.
+    pub fn reload(&mut self) -> ScriptResult<&Environment> {
+        self.loaded = true;
+        let previous = std::mem::take(&mut self.files);
+        let mut files = vec![self.path.clone()];
+
+        let result = self.evaluate(&mut files);
+        // A module that failed to import isn't in the module cache, so keep watching the files
+        // from before along with those in the traceback
+        if let Err(error) = &result {
+            files.extend(previous.into_iter().map(|(path, _)| path));
+            if let Some(traceback) = error.traceback() {
+                files.extend(traceback.frames.into_iter().map(|frame| frame.file.into()));
+            }
+        }
+
+        files.sort();
+        files.dedup();
+        self.files = files
+            .into_iter()
+            .filter(|path| path.is_file())
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect();
+        // The script itself is watched even while it doesn't exist
+        if !self.files.iter().any(|(path, _)| *path == self.path) {
+            self.files.push((self.path.clone(), None));
+        }
+
+        let environment = result?;
+        Ok(self.environment.insert(environment))
+    }
+
+    fn changed(&self) -> bool {
+        self.files
+            .iter()
+            .any(|(path, modified_at)| modified(path) != *modified_at)
+    }
+
+    fn evaluate(&mut self, files: &mut Vec<PathBuf>) -> ScriptResult<Environment> {
+        let core = CoreRef::new(self.flags);
+        let mut environment = Environment::new(&core)?;
+
+        let root = self
+            .path
+            .canonicalize()
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("."));
+        environment.set_var("__rustsynth_root", root.to_string_lossy().into_owned())?;
+        let preloaded = match &self.preloaded {
+            Some(preloaded) => preloaded.clone(),
+            None => {
+                environment
+                    .eval_script("__rustsynth_preloaded = '\\n'.join(__import__('sys').modules)")?;
+                let preloaded = environment.get_var::<String>("__rustsynth_preloaded")?;
+                self.preloaded.insert(preloaded).clone()
+            }
+        };
+        environment.set_var("__rustsynth_preloaded", preloaded)?;
+        environment.eval_script(&local_modules_snippet(true))?;
+
+        if let Some(setup) = &self.setup {
+            setup(&environment)?;
+        }
+        let result = environment.eval_file(&self.path);
+
+        if environment
+            .eval_script(&local_modules_snippet(false))
+            .is_ok()
+        {
+            if let Ok(modules) = environment.get_var::<String>("__rustsynth_modules") {
+                files.extend(modules.lines().map(PathBuf::from));
+            }
+        }
+        let _ = environment
+            .eval_script("del __rustsynth_modules, __rustsynth_preloaded, __rustsynth_root");
+
+        result.map(|()| environment)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,216 @@
+//! Tests of the `IntoOwnedMap` and `FromMap` derives, which need the `proc-macro` feature.
+
+use rustsynth::core::{CoreCreationFlags, CoreRef};
+use rustsynth::format::PresetVideoFormat;
+use rustsynth::frame::Frame;
+use rustsynth::function::Function;
+use rustsynth::map::{FromMap, IntoOwnedMap, Map, MapError};
+use rustsynth::node::Node;
+use rustsynth::{FromMap, IntoOwnedMap};
+
+/// Creates a core, which also loads the API used by maps.
+fn setup_core() -> CoreRef<'static> {
+    CoreRef::new(CoreCreationFlags::NONE)
+}
+
+fn blank_clip<'core>(core: &CoreRef<'core>) -> Node<'core> {
+    let mut args = Map::new().unwrap();
+    args.set_int("width", 640).unwrap();
+    args.set_int("height", 480).unwrap();
+    args.set_int("length", 10).unwrap();
+    args.set_int("format", PresetVideoFormat::YUV420P8 as i64)
+        .unwrap();
+    core.plugin_by_namespace("std")
+        .unwrap()
+        .invoke("BlankClip", &args)
+        .unwrap()
+        .get_node("clip")
+        .unwrap()
+}
+
+fn default_radius() -> i64 {
+    1
+}
+
+#[derive(IntoOwnedMap, FromMap, Debug, PartialEq)]
+struct Plain {
+    width: i64,
+    scale: f64,
+    #[map(rename = "name")]
+    title: String,
+    #[map(skip)]
+    cache: Vec<u8>,
+    #[map(default)]
+    r#type: String,
+    #[map(default = "default_radius")]
+    radius: i64,
+    mode: Option<String>,
+    planes: Vec<i64>,
+    weights: Option<Vec<f64>>,
+}
+
+fn plain() -> Plain {
+    Plain {
+        width: 640,
+        scale: 0.5,
+        title: "clip".to_string(),
+        cache: vec![1, 2, 3],
+        r#type: "fast".to_string(),
+        radius: 3,
+        mode: Some("bicubic".to_string()),
+        planes: vec![0, 2],
+        weights: Some(vec![0.25, 0.75]),
+    }
+}
+
+#[test]
+fn test_plain_round_trip() {
+    let _core = setup_core();
+    let map = plain().into_owned_map().unwrap();
+
+    assert_eq!(map.get::<i64>("width").unwrap(), 640);
+    assert_eq!(map.get::<String>("name").unwrap(), "clip");
+    assert_eq!(map.get::<String>("type").unwrap(), "fast");
+    assert_eq!(map.value_count("planes").unwrap(), 2);
+    assert_eq!(map.value_count("weights").unwrap(), 2);
+    // Renamed and skipped fields aren't stored under the field name
+    assert!(map.get::<String>("title").is_err());
+    assert!(map.get::<i64>("cache").is_err());
+    assert_eq!(map.key_count(), 8);
+
+    // The skipped field comes back as its default
+    let expected = Plain {
+        cache: Vec::new(),
+        ..plain()
+    };
+    assert_eq!(Plain::from_map(&map).unwrap(), expected);
+}
+
+#[test]
+fn test_plain_missing_keys() {
+    let _core = setup_core();
+    let plain = Plain {
+        r#type: String::new(),
+        mode: None,
+        planes: Vec::new(),
+        weights: None,
+        ..plain()
+    };
+    let mut map = plain.into_owned_map().unwrap();
+    // `None` and empty fields aren't stored
+    assert_eq!(map.key_count(), 5);
+
+    map.delete_key("radius").unwrap();
+    let read = Plain::from_map(&map).unwrap();
+    assert_eq!(read.radius, 1);
+    assert_eq!(read.r#type, "");
+    assert_eq!(read.mode, None);
+    assert_eq!(read.planes, Vec::<i64>::new());
+    assert_eq!(read.weights, None);
+
+    map.delete_key("name").unwrap();
+    assert_eq!(
+        Plain::from_map(&map).unwrap_err(),
+        MapError::MissingField {
+            key: "name",
+            field: "Plain::title",
+        }
+    );
+    assert_eq!(
+        MapError::MissingField {
+            key: "name",
+            field: "Plain::title",
+        }
+        .to_string(),
+        "The key `name` of the field `Plain::title` wasn't found in the map"
+    );
+}
+
+#[test]
+fn test_wrong_value_type() {
+    let _core = setup_core();
+    let mut map = plain().into_owned_map().unwrap();
+    map.set_string("width", "wide").unwrap();
+    assert_eq!(Plain::from_map(&map).unwrap_err(), MapError::WrongValueType);
+}
+
+#[derive(IntoOwnedMap, FromMap)]
+struct Handles<'core> {
+    clip: Node<'core>,
+    frames: Vec<Frame<'core>>,
+    callback: Option<Function<'core>>,
+}
+
+#[test]
+fn test_handles_round_trip() {
+    let core = setup_core();
+    let clip = blank_clip(&core);
+    let frames = vec![clip.get_frame(0).unwrap(), clip.get_frame(1).unwrap()];
+    let callback = Function::new(core, |_, _, out| {
+        out.set_int("val", 42).unwrap();
+    });
+    let handles = Handles {
+        clip,
+        frames,
+        callback: Some(callback),
+    };
+
+    let map = handles.into_owned_map().unwrap();
+    let read = Handles::from_map(&map).unwrap();
+    assert_eq!(read.clip.video_info().unwrap().num_frames, 10);
+    assert_eq!(read.frames.len(), 2);
+    assert_eq!(read.frames[1].get_width(0), 640);
+    let out = read
+        .callback
+        .unwrap()
+        .try_call(&Map::new().unwrap())
+        .unwrap();
+    assert_eq!(out.get_int("val").unwrap(), 42);
+}
+
+/// The example of the `IntoOwnedMap` derive documentation.
+#[test]
+fn test_into_owned_map_example() {
+    let _core = setup_core();
+
+    #[derive(IntoOwnedMap)]
+    struct MyStruct {
+        field1: i64,
+        #[map(rename = "name")]
+        field2: String,
+        planes: Vec<i64>,
+    }
+    let s = MyStruct {
+        field1: 42,
+        field2: "Hello".to_string(),
+        planes: vec![0, 1],
+    };
+    let map = s.into_owned_map().unwrap();
+    assert_eq!(map.get::<i64>("field1").unwrap(), 42);
+    assert_eq!(map.get::<String>("name").unwrap(), "Hello");
+    assert_eq!(map.value_count("planes").unwrap(), 2);
+}
+
+/// The example of the `FromMap` derive documentation.
+#[test]
+fn test_from_map_example() {
+    #[derive(FromMap)]
+    struct BlurArgs<'core> {
+        clip: Node<'core>,
+        #[map(default = "default_radius")]
+        radius: i64,
+        planes: Vec<i64>,
+        #[map(rename = "mode")]
+        blur_mode: Option<String>,
+    }
+
+    let core = setup_core();
+    let mut args = Map::new().unwrap();
+    args.set_node("clip", &blank_clip(&core)).unwrap();
+    args.set_string("mode", "box").unwrap();
+    let args = BlurArgs::from_map(&args).unwrap();
+    assert_eq!(args.clip.video_info().unwrap().num_frames, 10);
+    assert_eq!(args.radius, 1);
+    assert!(args.planes.is_empty());
+    assert_eq!(args.blur_mode.as_deref(), Some("box"));
+}